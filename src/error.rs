@@ -0,0 +1,119 @@
+use std::fmt;
+use std::io;
+use std::path;
+
+/// Exit status if at least one entry was printed.
+pub const EXIT_MATCH: i32 = 0;
+/// Exit status if no entry matched the time span.
+pub const EXIT_NOMATCH: i32 = 1;
+/// Exit status for invalid command-line arguments.
+pub const EXIT_USAGE: i32 = 2;
+/// Exit status for input/output errors.
+pub const EXIT_IO: i32 = 3;
+/// Exit status for malformed calendar files.
+pub const EXIT_PARSE: i32 = 4;
+
+/// Errors which can occur while parsing command-line arguments.
+#[derive(Debug, PartialEq)]
+pub enum ArgError {
+    InvalidDate(time::error::Parse),
+    UnknownOffset(time::error::IndeterminateOffset),
+    OutOfRange(time::error::ComponentRange),
+    UnknownDate(String),
+    AmbiguousDate(String, &'static str),
+    InvalidPeriod(String),
+    InvalidPriority(String),
+}
+
+impl fmt::Display for ArgError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ArgError::InvalidDate(e) => write!(f, "invalid date: {}", e),
+            ArgError::UnknownOffset(e) => write!(f, "{}", e),
+            ArgError::OutOfRange(e) => write!(f, "invalid date: {}", e),
+            ArgError::UnknownDate(s) => write!(f, "unknown date format '{}'", s),
+            ArgError::AmbiguousDate(s, hint) => write!(f, "ambiguous date '{}', {}", s, hint),
+            ArgError::InvalidPriority(s) => {
+                write!(f, "invalid priority '{}', expected low, medium or high", s)
+            }
+            ArgError::InvalidPeriod(s) => {
+                write!(
+                    f,
+                    "invalid period '{}', expected e.g. 3, 2w, 1m, 1y or eom",
+                    s
+                )
+            }
+        }
+    }
+}
+
+impl From<time::error::Parse> for ArgError {
+    fn from(e: time::error::Parse) -> Self {
+        ArgError::InvalidDate(e)
+    }
+}
+
+impl From<time::error::ComponentRange> for ArgError {
+    fn from(e: time::error::ComponentRange) -> Self {
+        ArgError::OutOfRange(e)
+    }
+}
+
+impl From<time::error::IndeterminateOffset> for ArgError {
+    fn from(e: time::error::IndeterminateOffset) -> Self {
+        ArgError::UnknownOffset(e)
+    }
+}
+
+/// Errors which can occur after the command-line was parsed.
+#[derive(Debug)]
+pub enum Error {
+    InvalidSpan,
+    NoLocation,
+    Calendar(path::PathBuf, ncalendar::error::Error),
+    Config(path::PathBuf, ncalendar::error::Error),
+    Output(io::Error),
+}
+
+impl Error {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::InvalidSpan => EXIT_USAGE,
+            Error::NoLocation => EXIT_USAGE,
+            Error::Calendar(_, ncalendar::error::Error::IoError(_)) => EXIT_IO,
+            Error::Calendar(_, _) => EXIT_PARSE,
+            Error::Config(_, ncalendar::error::Error::IoError(_)) => EXIT_IO,
+            Error::Config(_, _) => EXIT_PARSE,
+            Error::Output(_) => EXIT_IO,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InvalidSpan => write!(f, "time span exceeds the supported date range"),
+            Error::NoLocation => write!(
+                f,
+                "latitude and longitude must be configured for --sun and DayLength entries"
+            ),
+            Error::Calendar(fp, e) | Error::Config(fp, e) => write!(f, "{}: {}", fp.display(), e),
+            Error::Output(e) => write!(f, "failed to write output: {}", e),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Output(e)
+    }
+}
+
+impl From<time::error::Format> for Error {
+    fn from(e: time::error::Format) -> Self {
+        match e {
+            time::error::Format::StdIo(e) => Error::Output(e),
+            e => Error::Output(io::Error::other(e)),
+        }
+    }
+}
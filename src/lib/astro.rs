@@ -0,0 +1,247 @@
+//! Computation of astronomical events using the algorithms from Jean
+//! Meeus' "Astronomical Algorithms" (2nd edition, chapters 27 and 49).
+//! All times are in UTC and accurate to a few minutes.
+
+use std::f64::consts::PI;
+use std::fmt;
+
+/// An astronomical event which takes place at a computed date.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Event {
+    NewMoon,
+    FullMoon,
+    MarchEquinox,
+    JuneSolstice,
+    SeptemberEquinox,
+    DecemberSolstice,
+}
+
+pub const EVENTS: [Event; 6] = [
+    Event::NewMoon,
+    Event::FullMoon,
+    Event::MarchEquinox,
+    Event::JuneSolstice,
+    Event::SeptemberEquinox,
+    Event::DecemberSolstice,
+];
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Event::NewMoon => "New moon",
+            Event::FullMoon => "Full moon",
+            Event::MarchEquinox => "March equinox",
+            Event::JuneSolstice => "June solstice",
+            Event::SeptemberEquinox => "September equinox",
+            Event::DecemberSolstice => "December solstice",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// Approximation of the difference between dynamical and universal time.
+const DELTA_T: f64 = 69.0;
+
+// Julian day of the unix epoch.
+const UNIX_EPOCH_JD: f64 = 2440587.5;
+
+pub(crate) fn sin(deg: f64) -> f64 {
+    (deg * PI / 180.0).sin()
+}
+
+pub(crate) fn cos(deg: f64) -> f64 {
+    (deg * PI / 180.0).cos()
+}
+
+// Convert a Julian Day to a UTC date and time.
+pub(crate) fn from_jd(jd: f64) -> Option<time::OffsetDateTime> {
+    let secs = (jd - UNIX_EPOCH_JD) * 86400.0;
+    time::OffsetDateTime::from_unix_timestamp(secs.round() as i64).ok()
+}
+
+// Convert a Julian Ephemeris Day to a UTC date and time.
+fn from_jde(jde: f64) -> Option<time::OffsetDateTime> {
+    from_jd(jde - DELTA_T / 86400.0)
+}
+
+// Time of the new moon (phase 0.0) or full moon (phase 0.5) for the
+// given lunation number k, where k = 0 is the new moon of 6th January 2000.
+fn moon_phase(k: f64) -> f64 {
+    let t = k / 1236.85;
+    let jde = 2451550.09766 + 29.530588861 * k + 0.00015437 * t.powi(2) - 0.000000150 * t.powi(3)
+        + 0.00000000073 * t.powi(4);
+
+    let e = 1.0 - 0.002516 * t - 0.0000074 * t.powi(2);
+    let m = 2.5534 + 29.10535670 * k - 0.0000014 * t.powi(2) - 0.00000011 * t.powi(3);
+    let mp = 201.5643 + 385.81693528 * k + 0.0107582 * t.powi(2) + 0.00001238 * t.powi(3)
+        - 0.000000058 * t.powi(4);
+    let f = 160.7108 + 390.67050284 * k - 0.0016118 * t.powi(2) - 0.00000227 * t.powi(3)
+        + 0.000000011 * t.powi(4);
+    let om = 124.7746 - 1.56375588 * k + 0.0020672 * t.powi(2) + 0.00000215 * t.powi(3);
+
+    // Only the first three coefficients differ for new and full moons.
+    let (c1, c2, c3, c4, c5, c6, c7) = if k.fract().abs() < 0.25 {
+        (
+            -0.40720, 0.17241, 0.01608, 0.01039, 0.00739, -0.00514, 0.00208,
+        )
+    } else {
+        (
+            -0.40614, 0.17302, 0.01614, 0.01043, 0.00734, -0.00515, 0.00209,
+        )
+    };
+
+    let corr = c1 * sin(mp)
+        + c2 * e * sin(m)
+        + c3 * sin(2.0 * mp)
+        + c4 * sin(2.0 * f)
+        + c5 * e * sin(mp - m)
+        + c6 * e * sin(mp + m)
+        + c7 * e * e * sin(2.0 * m)
+        - 0.00111 * sin(mp - 2.0 * f)
+        - 0.00057 * sin(mp + 2.0 * f)
+        + 0.00056 * e * sin(2.0 * mp + m)
+        - 0.00042 * sin(3.0 * mp)
+        + 0.00042 * e * sin(m + 2.0 * f)
+        + 0.00038 * e * sin(m - 2.0 * f)
+        - 0.00024 * e * sin(2.0 * mp - m)
+        - 0.00017 * sin(om)
+        - 0.00007 * sin(mp + 2.0 * m)
+        + 0.00004 * sin(2.0 * mp - 2.0 * f)
+        + 0.00004 * sin(3.0 * m)
+        + 0.00003 * sin(mp + m - 2.0 * f)
+        + 0.00003 * sin(2.0 * mp + 2.0 * f)
+        - 0.00003 * sin(mp + m + 2.0 * f)
+        + 0.00003 * sin(mp - m + 2.0 * f)
+        - 0.00002 * sin(mp - m - 2.0 * f)
+        - 0.00002 * sin(3.0 * mp + m)
+        + 0.00002 * sin(4.0 * mp);
+
+    jde + corr
+}
+
+// Periodic terms for the computation of equinoxes and solstices.
+const SEASON_TERMS: [(f64, f64, f64); 24] = [
+    (485.0, 324.96, 1934.136),
+    (203.0, 337.23, 32964.467),
+    (199.0, 342.08, 20.186),
+    (182.0, 27.85, 445267.112),
+    (156.0, 73.14, 45036.886),
+    (136.0, 171.52, 22518.443),
+    (77.0, 222.54, 65928.934),
+    (74.0, 296.72, 3034.906),
+    (70.0, 243.58, 9037.513),
+    (58.0, 119.81, 33718.147),
+    (52.0, 297.17, 150.678),
+    (50.0, 21.02, 2281.226),
+    (45.0, 247.54, 29929.562),
+    (44.0, 325.15, 31555.956),
+    (29.0, 60.93, 4443.417),
+    (18.0, 155.12, 67555.328),
+    (17.0, 288.79, 4562.452),
+    (16.0, 198.04, 62894.029),
+    (14.0, 199.76, 31436.921),
+    (12.0, 95.39, 14577.848),
+    (12.0, 287.11, 31931.756),
+    (12.0, 320.81, 34777.259),
+    (9.0, 227.73, 1222.114),
+    (8.0, 15.45, 16859.074),
+];
+
+// Time of the given equinox or solstice in the given year.
+fn season(event: Event, year: i32) -> f64 {
+    let y = (f64::from(year) - 2000.0) / 1000.0;
+    let coeffs = match event {
+        Event::MarchEquinox => [2451623.80984, 365242.37404, 0.05169, -0.00411, -0.00057],
+        Event::JuneSolstice => [2451716.56767, 365241.62603, 0.00325, 0.00888, -0.00030],
+        Event::SeptemberEquinox => [2451810.21715, 365242.01767, -0.11575, 0.00337, 0.00078],
+        _ => [2451900.05952, 365242.74049, -0.06223, -0.00823, 0.00032],
+    };
+    let jde0 = coeffs
+        .iter()
+        .enumerate()
+        .map(|(i, c)| c * y.powi(i as i32))
+        .sum::<f64>();
+
+    let t = (jde0 - 2451545.0) / 36525.0;
+    let w = 35999.373 * t - 2.47;
+    let dl = 1.0 + 0.0334 * cos(w) + 0.0007 * cos(2.0 * w);
+    let s: f64 = SEASON_TERMS
+        .iter()
+        .map(|(a, b, c)| a * cos(b + c * t))
+        .sum();
+
+    jde0 + 0.00001 * s / dl
+}
+
+impl Event {
+    /// Returns the time of the event if it takes place on the given date.
+    pub fn time(&self, date: time::Date) -> Option<time::OffsetDateTime> {
+        let jde = match self {
+            Event::NewMoon | Event::FullMoon => {
+                let phase = if *self == Event::NewMoon { 0.0 } else { 0.5 };
+                let year = f64::from(date.year()) + f64::from(date.ordinal() - 1) / 365.25;
+                let k = ((year - 2000.0) * 12.3685).floor();
+
+                // Check the adjacent lunations as k is only an approximation.
+                return (-1..=1)
+                    .filter_map(|i| from_jde(moon_phase(k + f64::from(i) + phase)))
+                    .find(|t| t.date() == date);
+            }
+            _ => season(*self, date.year()),
+        };
+
+        from_jde(jde).filter(|t| t.date() == date)
+    }
+
+    pub fn matches(&self, date: time::Date) -> bool {
+        self.time(date).is_some()
+    }
+}
+
+/// Returns all events which take place on the given date.
+pub fn events(date: time::Date) -> Vec<(Event, time::OffsetDateTime)> {
+    EVENTS
+        .iter()
+        .filter_map(|e| e.time(date).map(|t| (*e, t)))
+        .collect()
+}
+
+////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::{date, datetime};
+
+    // Compare times with an accuracy of five minutes.
+    fn assert_near(event: Event, expected: time::OffsetDateTime) {
+        let t = event.time(expected.date()).unwrap();
+        assert!((t - expected).abs() < time::Duration::minutes(5), "{}", t);
+    }
+
+    #[test]
+    fn seasons() {
+        assert_near(Event::MarchEquinox, datetime!(2024-03-20 03:06 UTC));
+        assert_near(Event::JuneSolstice, datetime!(2024-06-20 20:51 UTC));
+        assert_near(Event::SeptemberEquinox, datetime!(2024-09-22 12:44 UTC));
+        assert_near(Event::DecemberSolstice, datetime!(2024-12-21 09:20 UTC));
+        assert!(!Event::DecemberSolstice.matches(date!(2024 - 12 - 22)));
+    }
+
+    #[test]
+    fn moon_phases() {
+        assert_near(Event::NewMoon, datetime!(2024-01-11 11:57 UTC));
+        assert_near(Event::FullMoon, datetime!(2024-01-25 17:54 UTC));
+        assert_near(Event::FullMoon, datetime!(2023-12-27 00:33 UTC));
+        assert_near(Event::NewMoon, datetime!(1999-12-07 22:32 UTC));
+        assert!(!Event::FullMoon.matches(date!(2024 - 01 - 24)));
+        assert!(!Event::NewMoon.matches(date!(2024 - 01 - 25)));
+    }
+
+    #[test]
+    fn all_events() {
+        let events = events(date!(2024 - 01 - 25));
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].0, Event::FullMoon);
+    }
+}
@@ -0,0 +1,333 @@
+//! Conversion between the Gregorian calendar and other calendars, based
+//! on the arithmetic algorithms from Reingold and Dershowitz' book
+//! "Calendrical Calculations". Dates are converted using fixed day
+//! numbers, counting days since the 1st January of the year 1 (Gregorian).
+
+pub type Fixed = i64;
+
+// Julian day number of the day before fixed day 1.
+const JD_OFFSET: i64 = 1721425;
+
+/// Returns the fixed day number of the given date.
+pub fn fixed_from_date(date: time::Date) -> Fixed {
+    i64::from(date.to_julian_day()) - JD_OFFSET
+}
+
+/// Returns the Gregorian date of the given fixed day number.
+pub fn date_from_fixed(fixed: Fixed) -> Option<time::Date> {
+    let jd = i32::try_from(fixed + JD_OFFSET).ok()?;
+    time::Date::from_julian_day(jd).ok()
+}
+
+////////////////////////////////////////////////////////////////////////
+
+/// Months of the Hebrew calendar, Adar I is only part of leap years.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum HebrewMonth {
+    Nisan,
+    Iyar,
+    Sivan,
+    Tammuz,
+    Av,
+    Elul,
+    Tishrei,
+    Cheshvan,
+    Kislev,
+    Tevet,
+    Shevat,
+    Adar, // Adar II in leap years
+    AdarI,
+    AdarII,
+}
+
+impl HebrewMonth {
+    // Number of the month in the given year, counting from Nisan.
+    fn number(&self, year: i64) -> u8 {
+        match self {
+            HebrewMonth::Adar | HebrewMonth::AdarII if hebrew_leap_year(year) => 13,
+            HebrewMonth::Adar | HebrewMonth::AdarI | HebrewMonth::AdarII => 12,
+            _ => *self as u8 + 1,
+        }
+    }
+}
+
+// Fixed day number of the 1st Tishrei of the year 1.
+const HEBREW_EPOCH: Fixed = -1373427;
+
+fn hebrew_leap_year(year: i64) -> bool {
+    (7 * year + 1).rem_euclid(19) < 7
+}
+
+fn last_month_of_hebrew_year(year: i64) -> u8 {
+    if hebrew_leap_year(year) {
+        13
+    } else {
+        12
+    }
+}
+
+// Days from the epoch to the new year, based on the time of the molad.
+fn hebrew_calendar_elapsed_days(year: i64) -> i64 {
+    let months = (235 * year - 234).div_euclid(19);
+    let parts = 12084 + 13753 * months;
+    let days = 29 * months + parts.div_euclid(25920);
+    if (3 * (days + 1)).rem_euclid(7) < 3 {
+        days + 1
+    } else {
+        days
+    }
+}
+
+// Delays of the new year to keep the length of the year within bounds.
+fn hebrew_year_length_correction(year: i64) -> i64 {
+    let ny0 = hebrew_calendar_elapsed_days(year - 1);
+    let ny1 = hebrew_calendar_elapsed_days(year);
+    let ny2 = hebrew_calendar_elapsed_days(year + 1);
+    if ny2 - ny1 == 356 {
+        2
+    } else if ny1 - ny0 == 382 {
+        1
+    } else {
+        0
+    }
+}
+
+fn hebrew_new_year(year: i64) -> Fixed {
+    HEBREW_EPOCH + hebrew_calendar_elapsed_days(year) + hebrew_year_length_correction(year)
+}
+
+fn last_day_of_hebrew_month(month: u8, year: i64) -> u8 {
+    let days = hebrew_new_year(year + 1) - hebrew_new_year(year);
+    let short = match month {
+        2 | 4 | 6 | 10 | 13 => true,
+        12 => !hebrew_leap_year(year),
+        8 => days % 10 != 5, // Cheshvan is long in complete years
+        9 => days % 10 == 3, // Kislev is short in deficient years
+        _ => false,
+    };
+
+    if short {
+        29
+    } else {
+        30
+    }
+}
+
+fn fixed_from_hebrew(year: i64, month: u8, day: u8) -> Fixed {
+    let months: Box<dyn Iterator<Item = u8>> = if month < 7 {
+        Box::new((7..=last_month_of_hebrew_year(year)).chain(1..month))
+    } else {
+        Box::new(7..month)
+    };
+
+    hebrew_new_year(year)
+        + i64::from(day)
+        + months
+            .map(|m| i64::from(last_day_of_hebrew_month(m, year)))
+            .sum::<i64>()
+        - 1
+}
+
+/// Returns the year, the month (counting from Nisan) and the day of the
+/// Hebrew calendar for the given date.
+pub fn hebrew_from_date(date: time::Date) -> (i64, u8, u8) {
+    let fixed = fixed_from_date(date);
+
+    let approx = ((fixed - HEBREW_EPOCH) as f64 / (35975351.0 / 98496.0)).floor() as i64 + 1;
+    let year = (approx - 1..=approx + 1)
+        .rev()
+        .find(|y| hebrew_new_year(*y) <= fixed)
+        .unwrap_or(approx - 1);
+
+    let start = if fixed < fixed_from_hebrew(year, 1, 1) {
+        7
+    } else {
+        1
+    };
+    let month = (start..=last_month_of_hebrew_year(year))
+        .find(|m| fixed <= fixed_from_hebrew(year, *m, last_day_of_hebrew_month(*m, year)))
+        .unwrap_or(start);
+    let day = fixed - fixed_from_hebrew(year, month, 1) + 1;
+
+    (year, month, day as u8)
+}
+
+/// Whether the given date is the given day of the given Hebrew month.
+pub fn is_hebrew_date(date: time::Date, day: u8, month: HebrewMonth) -> bool {
+    let (y, m, d) = hebrew_from_date(date);
+    d == day && m == month.number(y)
+}
+
+////////////////////////////////////////////////////////////////////////
+
+// Fixed day number of the 1st Muharram of the year 1 (16th July 622, Julian).
+const ISLAMIC_EPOCH: Fixed = 227015;
+
+fn fixed_from_islamic(year: i64, month: u8, day: u8) -> Fixed {
+    let month = i64::from(month);
+    ISLAMIC_EPOCH - 1
+        + (year - 1) * 354
+        + (3 + 11 * year).div_euclid(30)
+        + 29 * (month - 1)
+        + month / 2
+        + i64::from(day)
+}
+
+/// Returns the year, the month and the day of the tabular Islamic
+/// calendar for the given date.
+pub fn islamic_from_date(date: time::Date) -> (i64, u8, u8) {
+    let fixed = fixed_from_date(date);
+
+    let year = (30 * (fixed - ISLAMIC_EPOCH) + 10646).div_euclid(10631);
+    let prior = fixed - fixed_from_islamic(year, 1, 1);
+    let month = (11 * prior + 330).div_euclid(325) as u8;
+    let day = fixed - fixed_from_islamic(year, month, 1) + 1;
+
+    (year, month, day as u8)
+}
+
+/// Whether the given date is the given day of the given month (1 is
+/// Muharram) of the tabular Islamic calendar.
+pub fn is_islamic_date(date: time::Date, day: u8, month: u8) -> bool {
+    let (_, m, d) = islamic_from_date(date);
+    d == day && m == month
+}
+
+////////////////////////////////////////////////////////////////////////
+
+// Fixed day number of the 1st January of the year 1 (Julian).
+const JULIAN_EPOCH: Fixed = -1;
+
+// Years are numbered astronomically, i.e. the year 0 is the year 1 BCE.
+fn julian_leap_year(year: i64) -> bool {
+    year.rem_euclid(4) == 0
+}
+
+fn fixed_from_julian(year: i64, month: u8, day: u8) -> Fixed {
+    let correction = match month {
+        1 | 2 => 0,
+        _ if julian_leap_year(year) => -1,
+        _ => -2,
+    };
+
+    JULIAN_EPOCH - 1
+        + 365 * (year - 1)
+        + (year - 1).div_euclid(4)
+        + (367 * i64::from(month) - 362).div_euclid(12)
+        + correction
+        + i64::from(day)
+}
+
+/// Returns the year, the month and the day of the Julian calendar for
+/// the given date.
+pub fn julian_from_date(date: time::Date) -> (i64, time::Month, u8) {
+    let fixed = fixed_from_date(date);
+
+    let year = (4 * (fixed - JULIAN_EPOCH) + 1464).div_euclid(1461);
+    let prior = fixed - fixed_from_julian(year, 1, 1);
+    let correction = if fixed < fixed_from_julian(year, 3, 1) {
+        0
+    } else if julian_leap_year(year) {
+        1
+    } else {
+        2
+    };
+    let month = (12 * (prior + correction) + 373).div_euclid(367) as u8;
+    let day = fixed - fixed_from_julian(year, month, 1) + 1;
+
+    // The month is always within 1 and 12.
+    let month = time::Month::try_from(month).unwrap_or(time::Month::January);
+    (year, month, day as u8)
+}
+
+/// Whether the given date is the given day of the given Julian month.
+pub fn is_julian_date(date: time::Date, day: u8, month: time::Month) -> bool {
+    let (_, m, d) = julian_from_date(date);
+    d == day && m == month
+}
+
+////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::date;
+
+    #[test]
+    fn fixed() {
+        assert_eq!(fixed_from_date(date!(0001 - 01 - 01)), 1);
+        assert_eq!(fixed_from_date(date!(1945 - 11 - 12)), 710347);
+        assert_eq!(date_from_fixed(710347), Some(date!(1945 - 11 - 12)));
+    }
+
+    #[test]
+    fn hebrew() {
+        assert_eq!(hebrew_from_date(date!(1945 - 11 - 12)), (5706, 9, 7));
+        assert_eq!(hebrew_from_date(date!(2024 - 10 - 03)), (5785, 7, 1));
+        assert_eq!(hebrew_from_date(date!(2024 - 04 - 23)), (5784, 1, 15));
+        assert_eq!(hebrew_from_date(date!(2025 - 09 - 22)), (5785, 6, 29));
+        assert_eq!(hebrew_from_date(date!(2025 - 09 - 23)), (5786, 7, 1));
+
+        // Purim is in Adar II in leap years.
+        assert!(is_hebrew_date(date!(2024 - 03 - 24), 14, HebrewMonth::Adar));
+        assert!(is_hebrew_date(
+            date!(2024 - 02 - 23),
+            14,
+            HebrewMonth::AdarI
+        ));
+        assert!(is_hebrew_date(date!(2025 - 03 - 14), 14, HebrewMonth::Adar));
+        assert!(is_hebrew_date(
+            date!(2025 - 03 - 14),
+            14,
+            HebrewMonth::AdarII
+        ));
+
+        // 30 Cheshvan does not exist in 5784.
+        for day in 0..60 {
+            let date = date!(2023 - 10 - 15) + time::Duration::days(day);
+            assert!(!is_hebrew_date(date, 30, HebrewMonth::Cheshvan));
+        }
+    }
+
+    #[test]
+    fn julian() {
+        assert_eq!(
+            julian_from_date(date!(1945 - 11 - 12)),
+            (1945, time::Month::October, 30)
+        );
+        assert_eq!(
+            julian_from_date(date!(1582 - 10 - 15)),
+            (1582, time::Month::October, 5)
+        );
+
+        // Orthodox Christmas
+        assert!(is_julian_date(
+            date!(2024 - 01 - 07),
+            25,
+            time::Month::December
+        ));
+        assert!(is_julian_date(
+            date!(2101 - 01 - 08),
+            25,
+            time::Month::December
+        ));
+
+        // The Julian leap day in 2100 is the 14th March (Gregorian).
+        assert!(is_julian_date(
+            date!(2100 - 03 - 14),
+            29,
+            time::Month::February
+        ));
+        assert!(!(1..=28).any(|d| {
+            let date = time::Date::from_calendar_date(2101, time::Month::March, d).unwrap();
+            is_julian_date(date, 29, time::Month::February)
+        }));
+    }
+
+    #[test]
+    fn islamic() {
+        assert_eq!(islamic_from_date(date!(1945 - 11 - 12)), (1364, 12, 6));
+        assert_eq!(islamic_from_date(date!(2024 - 03 - 11)), (1445, 9, 1));
+        assert!(is_islamic_date(date!(2024 - 04 - 10), 1, 10));
+    }
+}
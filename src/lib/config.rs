@@ -0,0 +1,364 @@
+use crate::error::Error;
+use crate::format::{parse_feb29, parse_weekdays};
+use crate::locale::{Language, Locale};
+use crate::sun::Location;
+use crate::util::*;
+use crate::{holidays, Entry, Feb29, Reminder};
+
+use nom::{
+    branch::alt,
+    combinator::{all_consuming, map_res, opt},
+    number::complete::double,
+    IResult,
+};
+
+/// Settings which are not part of the calendar file itself.
+#[derive(Debug, PartialEq)]
+pub struct Config {
+    /// Days of the week which are not working days.
+    pub weekend: Vec<time::Weekday>,
+
+    /// Whether monthly days are clamped to the last day of shorter months.
+    pub clamp: bool,
+
+    /// Default policy for yearly entries on the 29th February, if any.
+    pub feb29: Option<Feb29>,
+
+    /// Latitude of the location used for sunrise and sunset times.
+    pub latitude: Option<f64>,
+
+    /// Longitude of the location used for sunrise and sunset times.
+    pub longitude: Option<f64>,
+
+    /// Names of the built-in holiday sets which are added to the calendar.
+    pub holidays: Vec<String>,
+
+    /// Language of weekday and month names, if configured.
+    pub language: Option<Language>,
+
+    /// Whether weekday and month names are matched case-insensitively.
+    pub ignore_case: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            weekend: vec![time::Weekday::Saturday, time::Weekday::Sunday],
+            clamp: false,
+            feb29: None,
+            latitude: None,
+            longitude: None,
+            holidays: Vec::new(),
+            language: None,
+            ignore_case: true,
+        }
+    }
+}
+
+fn parse_weekend(locale: Locale) -> impl Fn(&str) -> IResult<&str, Vec<time::Weekday>> {
+    move |input| {
+        let (input, wdays) = opt(parse_weekdays(locale))(input)?;
+        Ok((input, wdays.unwrap_or_default()))
+    }
+}
+
+fn parse_bool(input: &str) -> IResult<&str, bool> {
+    alt((str("yes", "true", true), str("no", "false", false)))(input)
+}
+
+// Parse a coordinate in degrees within the given bound.
+fn parse_degrees(bound: f64) -> impl FnMut(&str) -> IResult<&str, f64> {
+    move |input| {
+        map_res(double, |deg| -> Result<f64, ()> {
+            if deg.abs() <= bound {
+                Ok(deg)
+            } else {
+                Err(())
+            }
+        })(input)
+    }
+}
+
+// Parse a comma-separated list of holiday set names.
+fn parse_holidays(input: &str) -> Result<Vec<String>, Error> {
+    input
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| match holidays::exists(name) {
+            true => Ok(name.to_string()),
+            false => Err(Error::InvalidConfig(name.to_string())),
+        })
+        .collect()
+}
+
+// Parse a value using the given parser, the entire value must be consumed.
+fn value<'a, O, F>(parser: F, input: &'a str) -> Result<O, Error>
+where
+    F: FnMut(&'a str) -> IResult<&'a str, O>,
+{
+    let (_, val) = all_consuming(parser)(input)?;
+    Ok(val)
+}
+
+impl Config {
+    /// Set the value of the configuration option with the given name.
+    pub fn set(&mut self, key: &str, val: &str) -> Result<(), Error> {
+        match key {
+            "weekend" => self.weekend = value(parse_weekend(self.locale(Language::English)), val)?,
+            "clamp" => self.clamp = value(parse_bool, val)?,
+            "feb29" => self.feb29 = Some(value(parse_feb29, val)?),
+            "latitude" => self.latitude = Some(value(parse_degrees(90.0), val)?),
+            "longitude" => self.longitude = Some(value(parse_degrees(180.0), val)?),
+            "holidays" => self.holidays = parse_holidays(val)?,
+            "language" => {
+                let lang = Language::from_code(val).ok_or(Error::InvalidConfig(val.to_string()))?;
+                self.language = Some(lang)
+            }
+            "ignore_case" => self.ignore_case = value(parse_bool, val)?,
+            _ => return Err(Error::InvalidConfig(key.to_string())),
+        };
+
+        Ok(())
+    }
+
+    /// Locale for weekday and month names, using the given language
+    /// if no language is configured.
+    pub fn locale(&self, fallback: Language) -> Locale {
+        Locale {
+            language: self.language.unwrap_or(fallback),
+            ignore_case: self.ignore_case,
+        }
+    }
+
+    /// Location for sunrise and sunset times, if both coordinates are set.
+    pub fn location(&self) -> Option<Location> {
+        Some(Location {
+            latitude: self.latitude?,
+            longitude: self.longitude?,
+        })
+    }
+
+    /// Apply global options to the reminders of the given entries and add
+    /// the configured built-in holidays. A built-in holiday is replaced by
+    /// any given holiday entry with the same reminder.
+    pub fn apply(&self, entries: &mut Vec<Entry>) {
+        let builtin = holidays::entries(&self.holidays)
+            .into_iter()
+            .filter(|h| !entries.iter().any(|e| e.holiday && e.day == h.day))
+            .collect::<Vec<_>>();
+        entries.splice(0..0, builtin);
+
+        for entry in entries.iter_mut() {
+            let base = entry.day.base_mut();
+            if let Reminder::Monthly(day, year) = *base {
+                if self.clamp {
+                    *base = Reminder::MonthlyClamped(day, year);
+                }
+            }
+            if let Reminder::DayLength(_, loc @ None) = base {
+                *loc = self.location();
+            }
+
+            if let Some(policy) = self.feb29 {
+                entry.day.set_feb29(policy);
+            }
+        }
+    }
+}
+
+/// Parse configuration options of the form `key = value` from a string.
+/// Empty lines and comments (starting with `#`) are ignored.
+pub fn parse_config(input: &str) -> Result<Config, Error> {
+    let mut options = Vec::new();
+    for line in input.lines() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        match line.split_once('=') {
+            Some((key, val)) => options.push((line, key.trim(), val.trim())),
+            None => return Err(Error::InvalidConfig(line.to_string())),
+        }
+    }
+
+    // Names in other options are parsed according to the language.
+    options.sort_by_key(|(_, key, _)| !matches!(*key, "language" | "ignore_case"));
+
+    let mut config = Config::default();
+    for (line, key, val) in options {
+        config
+            .set(key, val)
+            .map_err(|_| Error::InvalidConfig(line.to_string()))?;
+    }
+
+    Ok(config)
+}
+
+////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weekend() {
+        let config = parse_config("# Working week\nweekend = Fri, Sat\n").unwrap();
+        assert_eq!(
+            config.weekend,
+            vec![time::Weekday::Friday, time::Weekday::Saturday]
+        );
+
+        let config = parse_config("weekend = Thu..Fri").unwrap();
+        assert_eq!(
+            config.weekend,
+            vec![time::Weekday::Thursday, time::Weekday::Friday]
+        );
+
+        let config = parse_config("weekend =\n").unwrap();
+        assert_eq!(config.weekend, vec![]);
+
+        assert!(parse_config("weekend = Fri Sat").is_err());
+        assert!(parse_config("weekday = Fri").is_err());
+    }
+
+    #[test]
+    fn language() {
+        let config = parse_config("weekend = vr, za\nlanguage = nl").unwrap();
+        assert_eq!(config.language, Some(Language::Dutch));
+        assert_eq!(
+            config.weekend,
+            vec![time::Weekday::Friday, time::Weekday::Saturday]
+        );
+
+        let config = parse_config("language = de\nignore_case = yes\nweekend = sa..SO").unwrap();
+        assert_eq!(
+            config.weekend,
+            vec![time::Weekday::Saturday, time::Weekday::Sunday]
+        );
+        assert_eq!(config.locale(Language::French).language, Language::German);
+        assert_eq!(
+            Config::default().locale(Language::French).language,
+            Language::French
+        );
+
+        assert!(parse_config("language = xx").is_err());
+    }
+
+    #[test]
+    fn clamp() {
+        let config = parse_config("clamp = yes").unwrap();
+        let mut entries = vec![Entry {
+            day: Reminder::Monthly(31, None),
+            desc: "Rent".to_string(),
+            lead: 0,
+            holiday: false,
+            meta: Default::default(),
+        }];
+
+        config.apply(&mut entries);
+        assert_eq!(entries[0].day, Reminder::MonthlyClamped(31, None));
+
+        assert!(!parse_config("clamp = no").unwrap().clamp);
+        assert!(parse_config("clamp = maybe").is_err());
+    }
+
+    #[test]
+    fn feb29() {
+        let config = parse_config("feb29 = mar1").unwrap();
+        let mut entries = vec![Entry {
+            day: Reminder::Anniversary(29, time::Month::February, 1996),
+            desc: "Birthday".to_string(),
+            lead: 0,
+            holiday: false,
+            meta: Default::default(),
+        }];
+
+        config.apply(&mut entries);
+        assert_eq!(
+            entries[0].day,
+            Reminder::LeapDay(
+                Box::new(Reminder::Anniversary(29, time::Month::February, 1996)),
+                Feb29::Mar1
+            )
+        );
+
+        assert!(entries.iter().all(|e| !e.day.skips_feb29()));
+
+        assert_eq!(parse_config("").unwrap().feb29, None);
+        assert_eq!(
+            parse_config("feb29 = skip").unwrap().feb29,
+            Some(Feb29::Skip)
+        );
+        assert!(parse_config("feb29 = mar2").is_err());
+    }
+
+    #[test]
+    fn location() {
+        let config = parse_config("latitude = 52.52\nlongitude = -13.405").unwrap();
+        assert_eq!(
+            config.location(),
+            Some(Location {
+                latitude: 52.52,
+                longitude: -13.405
+            })
+        );
+
+        let mut entries = vec![Entry {
+            day: Reminder::DayLength(time::Duration::hours(12), None),
+            desc: "Equilux".to_string(),
+            lead: 0,
+            holiday: false,
+            meta: Default::default(),
+        }];
+        config.apply(&mut entries);
+        assert_eq!(
+            entries[0].day,
+            Reminder::DayLength(time::Duration::hours(12), config.location())
+        );
+
+        assert_eq!(parse_config("latitude = 52.52").unwrap().location(), None);
+        assert!(parse_config("latitude = 91").is_err());
+        assert!(parse_config("longitude = east").is_err());
+    }
+
+    #[test]
+    fn builtin_holidays() {
+        let config = parse_config("holidays = de-BY, us").unwrap();
+        assert_eq!(config.holidays, vec!["de-BY", "us"]);
+        assert!(parse_config("holidays = de-XX").is_err());
+        assert!(parse_config("holidays =").unwrap().holidays.is_empty());
+
+        // Boxing Day is replaced by the given holiday entry.
+        let boxing = Reminder::Yearly(26, time::Month::December);
+        let mut entries = vec![Entry {
+            day: boxing.clone(),
+            desc: "Boxing Day".to_string(),
+            lead: 0,
+            holiday: true,
+            meta: Default::default(),
+        }];
+        config.apply(&mut entries);
+        assert_eq!(entries.len(), 22);
+        assert!(entries[0].holiday);
+        assert_eq!(entries.iter().filter(|e| e.day == boxing).count(), 1);
+
+        // An ordinary entry on the same day keeps the built-in holiday.
+        let mut entries = vec![Entry {
+            day: Reminder::Yearly(1, time::Month::January),
+            desc: "Hangover".to_string(),
+            lead: 0,
+            holiday: false,
+            meta: Default::default(),
+        }];
+        config.apply(&mut entries);
+        assert_eq!(entries.len(), 23);
+        let new_year: Vec<_> = entries
+            .iter()
+            .filter(|e| e.day == Reminder::Yearly(1, time::Month::January))
+            .collect();
+        assert_eq!(new_year.len(), 2);
+        assert!(new_year.iter().any(|e| e.holiday));
+    }
+}
@@ -14,11 +14,11 @@ pub fn has_cpp() -> bool {
     // Unfournuately, std::process doesn't have a function to iterate
     // over binaries in $PATH (e.g. analog to Go's LookPath) and we
     // can't check the spawn error for ENOENT either it seems.
-    return Command::new("cpp")
+    Command::new("cpp")
         .stdin(Stdio::null())
         .stdout(Stdio::null())
         .spawn()
-        .is_ok();
+        .is_ok()
 }
 
 pub fn preprocess<P: convert::AsRef<path::Path>>(fp: P) -> Result<String, Error> {
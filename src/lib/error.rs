@@ -1,3 +1,5 @@
+use std::error;
+use std::fmt;
 use std::io;
 use std::str;
 
@@ -6,7 +8,48 @@ pub enum Error {
     IncompleteParse,
     EncodingError(str::Utf8Error),
     ParsingError(String, nom::error::ErrorKind),
+    UnknownName(String, String, &'static str), // Entry, name and suggestion
+    AmbiguousLead(String, u32),                // Entry and lead time
     IoError(io::Error),
+    InvalidConfig(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::IncompleteParse => write!(f, "unexpected end of input"),
+            Error::EncodingError(e) => write!(f, "invalid encoding: {}", e),
+            Error::ParsingError(input, _) => {
+                // Only report the first non-empty line of the remaining
+                // input, which should contain the malformed calendar entry.
+                let line = input.lines().find(|l| !l.trim().is_empty());
+                let line = line.unwrap_or("");
+                write!(f, "invalid entry '{}'", line)
+            }
+            Error::UnknownName(line, name, suggestion) => write!(
+                f,
+                "invalid entry '{}', unknown name '{}' (did you mean '{}'?)",
+                line, name, suggestion
+            ),
+            Error::AmbiguousLead(line, n) => write!(
+                f,
+                "invalid entry '{}', ambiguous lead time '+{}' (did you mean '+{}d'?)",
+                line, n, n
+            ),
+            Error::IoError(e) => write!(f, "{}", e),
+            Error::InvalidConfig(s) => write!(f, "invalid configuration option '{}'", s),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::EncodingError(e) => Some(e),
+            Error::IoError(e) => Some(e),
+            _ => None,
+        }
+    }
 }
 
 impl From<nom::Err<nom::error::Error<&str>>> for Error {
@@ -1,28 +1,48 @@
+use crate::locale::{self, Locale};
 use crate::util::*;
 use crate::*;
 
 use nom::{
     branch::alt,
-    character::complete::{char, line_ending, not_line_ending, one_of},
-    combinator::{map_res, opt},
-    multi::many0,
-    sequence::{preceded, terminated, tuple},
+    bytes::complete::{tag, tag_no_case, take_while_m_n},
+    character::complete::{char, line_ending, not_line_ending, one_of, satisfy},
+    combinator::{map_res, not, opt},
+    multi::{many0, many1},
+    sequence::{pair, preceded, terminated, tuple},
     IResult,
 };
 use std::num::TryFromIntError;
 
 ////////////////////////////////////////////////////////////////////////
 
-fn parse_weekday(input: &str) -> IResult<&str, time::Weekday> {
-    alt((
-        str("Monday", "Mon", time::Weekday::Monday),
-        str("Tuesday", "Tue", time::Weekday::Tuesday),
-        str("Wednesday", "Wed", time::Weekday::Wednesday),
-        str("Thursday", "Thu", time::Weekday::Thursday),
-        str("Friday", "Fri", time::Weekday::Friday),
-        str("Saturday", "Sat", time::Weekday::Saturday),
-        str("Sunday", "Sun", time::Weekday::Sunday),
-    ))(input)
+// Parse a name at the start of the input, which is either one of the given
+// names or an unambiguous prefix (of at least three letters) of them.
+fn parse_name<T: Copy + PartialEq>(
+    input: &str,
+    names: impl Iterator<Item = (&'static str, T)> + Clone,
+    ignore_case: bool,
+) -> IResult<&str, T> {
+    let len = input
+        .find(|c: char| !c.is_alphabetic())
+        .unwrap_or(input.len());
+
+    match locale::find_name(&input[..len], names, ignore_case) {
+        Some(val) => Ok((&input[len..], val)),
+        None => Err(nom::Err::Error(nom::error::Error::new(
+            input,
+            nom::error::ErrorKind::Tag,
+        ))),
+    }
+}
+
+fn parse_weekday<'a>(locale: Locale) -> impl Fn(&'a str) -> IResult<&'a str, time::Weekday> + Copy {
+    move |input| parse_name(input, locale.weekdays(), locale.ignore_case)
+}
+
+pub fn parse_weekdays<'a>(
+    locale: Locale,
+) -> impl Fn(&'a str) -> IResult<&'a str, Vec<time::Weekday>> + Copy {
+    move |input| list(parse_weekday(locale), time::Weekday::next)(input)
 }
 
 fn parse_offset(input: &str) -> IResult<&str, WeekOffset> {
@@ -39,26 +59,13 @@ fn parse_offset(input: &str) -> IResult<&str, WeekOffset> {
         input,
         WeekOffset {
             from_start: prefix == '+',
-            amount: amount,
+            amount,
         },
     ))
 }
 
-fn parse_month_str(input: &str) -> IResult<&str, time::Month> {
-    alt((
-        str("January", "Jan", time::Month::January),
-        str("February", "Feb", time::Month::February),
-        str("March", "Mar", time::Month::March),
-        str("April", "Apr", time::Month::April),
-        str("May", "May", time::Month::May),
-        str("June", "Jun", time::Month::June),
-        str("July", "Jul", time::Month::July),
-        str("August", "Aug", time::Month::August),
-        str("September", "Sep", time::Month::September),
-        str("October", "Oct", time::Month::October),
-        str("November", "Nov", time::Month::November),
-        str("December", "Dec", time::Month::December),
-    ))(input)
+fn parse_month_str<'a>(locale: Locale) -> impl Fn(&'a str) -> IResult<&'a str, time::Month> + Copy {
+    move |input| parse_name(input, locale.months(), locale.ignore_case)
 }
 
 fn parse_month_num(input: &str) -> IResult<&str, time::Month> {
@@ -74,46 +81,419 @@ fn parse_month_num(input: &str) -> IResult<&str, time::Month> {
     )(input)
 }
 
-fn parse_month(input: &str) -> IResult<&str, time::Month> {
-    alt((parse_month_str, parse_month_num))(input)
+fn parse_month<'a>(locale: Locale) -> impl Fn(&'a str) -> IResult<&'a str, time::Month> + Copy {
+    move |input| alt((parse_month_str(locale), parse_month_num))(input)
+}
+
+fn parse_months<'a>(
+    locale: Locale,
+) -> impl Fn(&'a str) -> IResult<&'a str, Vec<time::Month>> + Copy {
+    move |input| list(parse_month(locale), time::Month::next)(input)
+}
+
+// Parse the name of a month of the Hebrew or Islamic calendar, which
+// must not be followed by further letters (e.g. `Av` in `Avril`).
+fn month_name<'a>(
+    name: &'static str,
+    locale: Locale,
+) -> impl Fn(&'a str) -> IResult<&'a str, &'a str> {
+    move |input| {
+        let (input, name) = if locale.ignore_case {
+            tag_no_case(name)(input)?
+        } else {
+            tag(name)(input)?
+        };
+        let (input, _) = not(satisfy(char::is_alphabetic))(input)?;
+        Ok((input, name))
+    }
+}
+
+fn parse_hebrew_month<'a>(
+    locale: Locale,
+) -> impl Fn(&'a str) -> IResult<&'a str, calendars::HebrewMonth> {
+    use calendars::HebrewMonth;
+
+    move |input| {
+        alt((
+            bind(month_name("Nisan", locale), HebrewMonth::Nisan),
+            bind(month_name("Iyar", locale), HebrewMonth::Iyar),
+            bind(month_name("Sivan", locale), HebrewMonth::Sivan),
+            bind(month_name("Tammuz", locale), HebrewMonth::Tammuz),
+            bind(month_name("Av", locale), HebrewMonth::Av),
+            bind(month_name("Elul", locale), HebrewMonth::Elul),
+            bind(
+                alt((month_name("Tishrei", locale), month_name("Tishri", locale))),
+                HebrewMonth::Tishrei,
+            ),
+            bind(
+                alt((
+                    month_name("Cheshvan", locale),
+                    month_name("Heshvan", locale),
+                    month_name("Marcheshvan", locale),
+                )),
+                HebrewMonth::Cheshvan,
+            ),
+            bind(month_name("Kislev", locale), HebrewMonth::Kislev),
+            bind(month_name("Tevet", locale), HebrewMonth::Tevet),
+            bind(month_name("Shevat", locale), HebrewMonth::Shevat),
+            // Match the longest name first, as Adar is a prefix of Adar I.
+            bind(month_name("Adar II", locale), HebrewMonth::AdarII),
+            bind(month_name("Adar I", locale), HebrewMonth::AdarI),
+            bind(month_name("Adar", locale), HebrewMonth::Adar),
+        ))(input)
+    }
+}
+
+fn parse_islamic_month<'a>(locale: Locale) -> impl Fn(&'a str) -> IResult<&'a str, u8> {
+    move |input| {
+        alt((
+            bind(month_name("Muharram", locale), 1),
+            bind(month_name("Safar", locale), 2),
+            bind(
+                alt((
+                    month_name("Rabi al-Thani", locale),
+                    month_name("Rabi II", locale),
+                )),
+                4,
+            ),
+            bind(
+                alt((
+                    month_name("Rabi al-Awwal", locale),
+                    month_name("Rabi I", locale),
+                )),
+                3,
+            ),
+            bind(
+                alt((
+                    month_name("Jumada al-Thani", locale),
+                    month_name("Jumada II", locale),
+                )),
+                6,
+            ),
+            bind(
+                alt((
+                    month_name("Jumada al-Awwal", locale),
+                    month_name("Jumada I", locale),
+                )),
+                5,
+            ),
+            bind(month_name("Rajab", locale), 7),
+            bind(month_name("Shaban", locale), 8),
+            bind(month_name("Ramadan", locale), 9),
+            bind(month_name("Shawwal", locale), 10),
+            bind(month_name("Dhu al-Qadah", locale), 11),
+            bind(month_name("Dhu al-Hijjah", locale), 12),
+        ))(input)
+    }
 }
 
 fn parse_day(input: &str) -> IResult<&str, Day> {
     map_res(digits, |n| -> Result<Day, TryFromIntError> { n.try_into() })(input)
 }
 
+// Parse a day relative to the end of the month (L is the last day).
+fn parse_day_from_end(input: &str) -> IResult<&str, Day> {
+    map_res(
+        preceded(char('L'), opt(preceded(char('-'), parse_day))),
+        |off| -> Result<Day, ()> {
+            // The longest months have 31 days, i.e. L-30 is their first day.
+            match off.unwrap_or(0) {
+                off @ 0..=30 => Ok(off),
+                _ => Err(()),
+            }
+        },
+    )(input)
+}
+
 fn parse_year(input: &str) -> IResult<&str, Year> {
     map_res(digits, |n| -> Result<Year, TryFromIntError> {
         n.try_into()
     })(input)
 }
 
-fn parse_reminder(input: &str) -> IResult<&str, Reminder> {
+// Parse a number with exactly the given amount of digits.
+fn fixed_digits<'a>(n: usize) -> impl Fn(&'a str) -> IResult<&'a str, u32> {
+    move |input| {
+        map_res(
+            take_while_m_n(n, n, |c: char| c.is_ascii_digit()),
+            str::parse,
+        )(input)
+    }
+}
+
+// Parse an ISO 8601 date (2024-12-20), month (2024-12) or a
+// date without a year (--12-20) which is used for yearly reminders.
+fn parse_iso_date(input: &str) -> IResult<&str, Reminder> {
     alt((
         map_res(
-            tuple((parse_weekday, parse_offset)),
-            |(wday, off)| -> Result<Reminder, ()> { Ok(Reminder::SemiWeekly(wday, off)) },
+            tuple((tag("--"), fixed_digits(2), char('-'), fixed_digits(2))),
+            |(_, mon, _, day)| -> Result<Reminder, time::error::ComponentRange> {
+                let mon = time::Month::try_from(mon as u8)?;
+                // The year 2000 is a leap year, allowing --02-29.
+                time::Date::from_calendar_date(2000, mon, day as u8)?;
+                Ok(Reminder::Yearly(day as Day, mon))
+            },
         ),
-        map_res(parse_weekday, |wday| -> Result<Reminder, ()> {
-            Ok(Reminder::Weekly(wday))
-        }),
         map_res(
-            tuple((parse_day, ws(char('*')), opt(parse_year))),
-            |(day, _, year)| -> Result<Reminder, ()> { Ok(Reminder::Monthly(day, year)) },
+            tuple((
+                fixed_digits(4),
+                char('-'),
+                fixed_digits(2),
+                opt(pair(preceded(char('-'), fixed_digits(2)), opt(char('*')))),
+            )),
+            |(year, _, mon, day)| -> Result<Reminder, time::error::ComponentRange> {
+                let mon = time::Month::try_from(mon as u8)?;
+                let (day, anniversary) = day.unwrap_or((1, None));
+                let date = time::Date::from_calendar_date(year as Year, mon, day as u8)?;
+
+                Ok(match anniversary {
+                    Some(_) => Reminder::Anniversary(date.day(), mon, date.year()),
+                    None => Reminder::Date(date),
+                })
+            },
         ),
-        map_res(
-            tuple((opt(parse_day), ws(parse_month), opt(parse_year))),
-            move |(day, mon, year)| -> Result<Reminder, time::error::ComponentRange> {
-                let day = day.unwrap_or(1);
-                Ok(match year {
-                    Some(y) => Reminder::Date(time::Date::from_calendar_date(y, mon, day)?),
-                    None => Reminder::Yearly(day, mon),
+    ))(input)
+}
+
+// Parse the Nth working day from the start (+) or end (-) of the month.
+fn parse_workday(input: &str) -> IResult<&str, i8> {
+    map_res(
+        preceded(tag("Workday"), pair(one_of("+-"), parse_day)),
+        |(prefix, n)| -> Result<i8, ()> {
+            let n = i8::try_from(n).map_err(|_| ())?;
+            match (prefix, n) {
+                (_, 0) => Err(()),
+                ('-', n) => Ok(-n),
+                (_, n) => Ok(n),
+            }
+        },
+    )(input)
+}
+
+fn parse_astro(input: &str) -> IResult<&str, astro::Event> {
+    alt((
+        bind(tag("NewMoon"), astro::Event::NewMoon),
+        bind(tag("FullMoon"), astro::Event::FullMoon),
+        bind(tag("MarEquinox"), astro::Event::MarchEquinox),
+        bind(tag("JunSolstice"), astro::Event::JuneSolstice),
+        bind(tag("SepEquinox"), astro::Event::SeptemberEquinox),
+        bind(tag("DecSolstice"), astro::Event::DecemberSolstice),
+    ))(input)
+}
+
+// Parse a day length like 10h or 9h30m.
+fn parse_day_length(input: &str) -> IResult<&str, time::Duration> {
+    map_res(
+        preceded(
+            pair(tag("DayLength"), many1(char(' '))),
+            pair(
+                terminated(digits, char('h')),
+                opt(terminated(digits, char('m'))),
+            ),
+        ),
+        |(h, m)| -> Result<time::Duration, ()> {
+            let len =
+                time::Duration::hours(h.into()) + time::Duration::minutes(m.unwrap_or(0).into());
+            if m.unwrap_or(0) >= 60 || len > time::Duration::DAY {
+                Err(())
+            } else {
+                Ok(len)
+            }
+        },
+    )(input)
+}
+
+fn parse_reminder<'a>(locale: Locale) -> impl Fn(&'a str) -> IResult<&'a str, Reminder> + Copy {
+    move |input| {
+        alt((
+            map_res(parse_day_length, |len| -> Result<Reminder, ()> {
+                Ok(Reminder::DayLength(len, None))
+            }),
+            map_res(tag("Easter"), |_| -> Result<Reminder, ()> {
+                Ok(Reminder::Easter)
+            }),
+            map_res(parse_astro, |event| -> Result<Reminder, ()> {
+                Ok(Reminder::Astro(event))
+            }),
+            parse_iso_date,
+            map_res(parse_workday, |n| -> Result<Reminder, ()> {
+                Ok(Reminder::Workday(n))
+            }),
+            map_res(
+                tuple((parse_weekday(locale), parse_offset, ws(parse_month(locale)))),
+                |(wday, off, mon)| -> Result<Reminder, ()> {
+                    Ok(Reminder::SemiWeeklyIn(wday, off, mon))
+                },
+            ),
+            map_res(
+                tuple((parse_weekday(locale), parse_offset)),
+                |(wday, off)| -> Result<Reminder, ()> { Ok(Reminder::SemiWeekly(wday, off)) },
+            ),
+            map_res(parse_weekdays(locale), |wdays| -> Result<Reminder, ()> {
+                Ok(match wdays[..] {
+                    [wday] => Reminder::Weekly(wday),
+                    _ => Reminder::WeeklyList(wdays),
                 })
+            }),
+            map_res(
+                tuple((parse_day, ws(char('*')), opt(parse_year))),
+                |(day, _, year)| -> Result<Reminder, ()> { Ok(Reminder::Monthly(day, year)) },
+            ),
+            map_res(
+                tuple((parse_day_from_end, ws(char('*')), opt(parse_year))),
+                |(off, _, year)| -> Result<Reminder, ()> {
+                    Ok(Reminder::MonthlyFromEnd(off, year))
+                },
+            ),
+            map_res(
+                tuple((parse_day, ws(parse_month(locale)), tag("julian"))),
+                |(day, mon, _)| -> Result<Reminder, ()> {
+                    // The year 2000 is a leap year in both calendars.
+                    let last = mon.length(2000);
+                    if (1..=last).contains(&day) {
+                        Ok(Reminder::Julian(day, mon))
+                    } else {
+                        Err(())
+                    }
+                },
+            ),
+            map_res(
+                pair(parse_day, ws(parse_hebrew_month(locale))),
+                |(day, mon)| -> Result<Reminder, ()> {
+                    match day {
+                        1..=30 => Ok(Reminder::Hebrew(day, mon)),
+                        _ => Err(()),
+                    }
+                },
+            ),
+            map_res(
+                pair(parse_day, ws(parse_islamic_month(locale))),
+                |(day, mon)| -> Result<Reminder, ()> {
+                    match day {
+                        1..=30 => Ok(Reminder::Islamic(day, mon)),
+                        _ => Err(()),
+                    }
+                },
+            ),
+            map_res(
+                tuple((
+                    opt(parse_day),
+                    ws(parse_months(locale)),
+                    opt(pair(parse_year, opt(char('*')))),
+                )),
+                move |(day, mons, year)| -> Result<Reminder, time::error::ComponentRange> {
+                    let day = day.unwrap_or(1);
+                    let mon = match (&mons[..], year) {
+                        ([mon], _) => *mon,
+                        (_, None) => return Ok(Reminder::YearlyList(day, mons)),
+                        // A list of months with a year is not supported, use an
+                        // invalid month to cause a ComponentRange error.
+                        (_, Some(_)) => time::Month::try_from(0)?,
+                    };
+
+                    Ok(match year {
+                        Some((y, None)) => {
+                            Reminder::Date(time::Date::from_calendar_date(y, mon, day)?)
+                        }
+                        Some((y, Some(_))) => {
+                            time::Date::from_calendar_date(y, mon, day)?;
+                            Reminder::Anniversary(day, mon, y)
+                        }
+                        None => Reminder::Yearly(day, mon),
+                    })
+                },
+            ),
+        ))(input)
+    }
+}
+
+pub fn parse_feb29(input: &str) -> IResult<&str, Feb29> {
+    alt((
+        bind(tag("feb28"), Feb29::Feb28),
+        bind(tag("mar1"), Feb29::Mar1),
+        bind(tag("skip"), Feb29::Skip),
+    ))(input)
+}
+
+fn parse_feb29_modifier(input: &str) -> IResult<&str, Feb29> {
+    preceded(tag("feb29="), parse_feb29)(input)
+}
+
+fn parse_week(input: &str) -> IResult<&str, u8> {
+    map_res(preceded(char('W'), digits), |n| -> Result<u8, ()> {
+        match n {
+            1..=53 => Ok(n as u8),
+            _ => Err(()),
+        }
+    })(input)
+}
+
+fn parse_week_filter(input: &str) -> IResult<&str, WeekFilter> {
+    preceded(
+        pair(tag("in"), many1(char(' '))),
+        alt((
+            bind(tag("odd weeks"), WeekFilter::Odd),
+            bind(tag("even weeks"), WeekFilter::Even),
+            map_res(
+                pair(parse_week, opt(preceded(char('-'), parse_week))),
+                |(start, end)| -> Result<WeekFilter, ()> {
+                    Ok(WeekFilter::Range(start, end.unwrap_or(start)))
+                },
+            ),
+        )),
+    )(input)
+}
+
+fn parse_year_filter(input: &str) -> IResult<&str, YearFilter> {
+    alt((
+        map_res(
+            tuple((
+                tag("every"),
+                ws(digits),
+                alt((tag("years"), tag("year"))),
+                ws(tag("from")),
+                parse_year,
+            )),
+            |(_, n, _, _, from)| -> Result<YearFilter, ()> {
+                if n == 0 {
+                    Err(())
+                } else {
+                    Ok(YearFilter::Every(n, from))
+                }
             },
         ),
+        bind(tag("odd years"), YearFilter::Odd),
+        bind(tag("even years"), YearFilter::Even),
+        bind(tag("leap years"), YearFilter::Leap),
+    ))(input)
+}
+
+// Parse an offset in days relative to a reminder (e.g. +1d or -3d).
+fn parse_day_offset(input: &str) -> IResult<&str, time::Duration> {
+    map_res(
+        terminated(pair(one_of("+-"), digits), char('d')),
+        |(prefix, n)| -> Result<time::Duration, ()> {
+            let days = i64::from(n);
+            Ok(time::Duration::days(if prefix == '-' {
+                -days
+            } else {
+                days
+            }))
+        },
+    )(input)
+}
+
+fn parse_shift(input: &str) -> IResult<&str, Shift> {
+    alt((
+        bind(tag(">workday"), Shift::Forward),
+        bind(tag("<workday"), Shift::Backward),
     ))(input)
 }
 
+fn parse_lead(input: &str) -> IResult<&str, u32> {
+    preceded(char('+'), digits)(input)
+}
+
 fn parse_desc(input: &str) -> IResult<&str, String> {
     let (input, (desc, ext)) = tuple((
         terminated(not_line_ending, line_ending),
@@ -130,14 +510,130 @@ fn parse_desc(input: &str) -> IResult<&str, String> {
     }
 }
 
-fn parse_entry(input: &str) -> IResult<&str, Entry> {
-    let (input, (day, _, desc)) = tuple((parse_reminder, char('\t'), parse_desc))(input)?;
+// Whether a lead time directly after the given reminder could be mistaken
+// for a day offset, e.g. `Thu+4 Nov +1` (instead of `Thu+4 Nov +1d`).
+fn is_ambiguous_lead(day: &Reminder) -> bool {
+    matches!(
+        day,
+        Reminder::SemiWeekly(_, _) | Reminder::SemiWeeklyIn(_, _, _) | Reminder::Workday(_)
+    )
+}
+
+fn parse_entry<'a>(locale: Locale) -> impl Fn(&'a str) -> IResult<&'a str, Entry> {
+    move |start| {
+        let (input, (day, feb29, weeks, years, off, shift, lead, holiday, _, desc)) =
+            tuple((
+                parse_reminder(locale),
+                opt(ws(parse_feb29_modifier)),
+                opt(ws(parse_week_filter)),
+                opt(ws(parse_year_filter)),
+                opt(ws(parse_day_offset)),
+                opt(ws(parse_shift)),
+                opt(ws(parse_lead)),
+                opt(ws(tag("holiday"))),
+                char('\t'),
+                parse_desc,
+            ))(start)?;
+
+        if off.is_none() && lead.is_some() && is_ambiguous_lead(&day) {
+            return Err(nom::Err::Error(nom::error::Error::new(
+                start,
+                nom::error::ErrorKind::Verify,
+            )));
+        }
+
+        let mut day = day;
+        if let Some(policy) = feb29 {
+            day.set_feb29(policy);
+        }
+
+        let day = match weeks {
+            Some(filter) => Reminder::InWeeks(Box::new(day), filter),
+            None => day,
+        };
+        let day = match years {
+            Some(filter) => Reminder::InYears(Box::new(day), filter),
+            None => day,
+        };
+        let day = match off {
+            Some(off) => Reminder::Offset(Box::new(day), off),
+            None => day,
+        };
+        let day = match shift {
+            Some(shift) => Reminder::Shifted(Box::new(day), shift),
+            None => day,
+        };
+
+        let (desc, meta) = meta::extract(&desc);
+        Ok((
+            input,
+            Entry {
+                day,
+                desc,
+                lead: lead.unwrap_or(0),
+                holiday: holiday.is_some(),
+                meta,
+            },
+        ))
+    }
+}
+
+// Words of the grammar which are not names of weekdays or months.
+const KEYWORDS: &[&str] = &[
+    "holiday",
+    "julian",
+    "in",
+    "odd",
+    "even",
+    "weeks",
+    "leap",
+    "every",
+    "year",
+    "years",
+    "from",
+    "skip",
+    "workday",
+    "Workday",
+    "Easter",
+    "DayLength",
+    "NewMoon",
+    "FullMoon",
+    "MarEquinox",
+    "JunSolstice",
+    "SepEquinox",
+    "DecSolstice",
+];
+
+/// Returns a word from the date of the given calendar entry which is
+/// likely a misspelled name of a weekday or month, along with that name.
+pub fn suggest(line: &str, locale: Locale) -> Option<(String, &'static str)> {
+    // Without a tab, the date can't be told apart from the description.
+    let (date, _) = line.split_once('\t')?;
+    date.split(|c: char| !c.is_alphabetic())
+        .filter(|word| !KEYWORDS.contains(word))
+        .filter(|word| {
+            parse_weekday(locale)(word).is_err() && parse_month_str(locale)(word).is_err()
+        })
+        .find_map(|word| Some((word.to_string(), locale.suggest(word)?)))
+}
+
+/// Returns the lead time of the given calendar entry if it was rejected
+/// as ambiguous, i.e. if it was likely meant as a day offset.
+pub fn ambiguous_lead(line: &str, locale: Locale) -> Option<u32> {
+    let (_, (day, _, _, _, lead)) = tuple((
+        parse_reminder(locale),
+        opt(ws(parse_feb29_modifier)),
+        opt(ws(parse_week_filter)),
+        opt(ws(parse_year_filter)),
+        ws(parse_lead),
+    ))(line)
+    .ok()?;
 
-    Ok((input, Entry { day, desc: desc }))
+    is_ambiguous_lead(&day).then_some(lead)
 }
 
-pub fn parse_entries(input: &str) -> IResult<&str, Vec<Entry>> {
-    many0(empty_lines(parse_entry))(input)
+pub fn parse_entries<'a>(locale: Locale) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<Entry>> {
+    many0(empty_lines(parse_entry(locale)))
 }
 
 ////////////////////////////////////////////////////////////////////////
@@ -145,29 +641,134 @@ pub fn parse_entries(input: &str) -> IResult<&str, Vec<Entry>> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::locale::Language;
     use nom::error::Error;
     use nom::error::ErrorKind;
     use nom::Err;
     use time::macros::date;
 
+    const EN: Locale = Locale {
+        language: Language::English,
+        ignore_case: false,
+    };
+
     #[test]
     fn weekday() {
-        assert_eq!(parse_weekday("Monday"), Ok(("", time::Weekday::Monday)));
-        assert_eq!(parse_weekday("Mon"), Ok(("", time::Weekday::Monday)));
-        assert_eq!(parse_weekday("Tuesday"), Ok(("", time::Weekday::Tuesday)));
+        assert_eq!(parse_weekday(EN)("Monday"), Ok(("", time::Weekday::Monday)));
+        assert_eq!(parse_weekday(EN)("Mon"), Ok(("", time::Weekday::Monday)));
+        assert_eq!(
+            parse_weekday(EN)("Tuesday"),
+            Ok(("", time::Weekday::Tuesday))
+        );
+    }
+
+    #[test]
+    fn names() {
+        let en = Locale::default();
+        assert_eq!(parse_weekday(en)("monday"), Ok(("", time::Weekday::Monday)));
+        assert_eq!(parse_weekday(en)("Tues"), Ok(("", time::Weekday::Tuesday)));
+        assert_eq!(
+            parse_weekday(en)("Weds"),
+            Ok(("", time::Weekday::Wednesday))
+        );
+        assert_eq!(
+            parse_weekday(en)("Thurs"),
+            Ok(("", time::Weekday::Thursday))
+        );
+        assert_eq!(
+            parse_weekday(en)("THU+2"),
+            Ok(("+2", time::Weekday::Thursday))
+        );
+        assert_eq!(parse_month(en)("SEPT"), Ok(("", time::Month::September)));
+        assert_eq!(parse_month(en)("Decem"), Ok(("", time::Month::December)));
+        assert!(parse_month(en)("Ju").is_err());
+        assert!(parse_weekday(EN)("monday").is_err());
+
+        // Ambiguous prefix of juin and juillet
+        let fr = Locale {
+            language: Language::French,
+            ignore_case: true,
+        };
+        assert!(parse_month(fr)("jui").is_err());
+
+        assert_eq!(
+            suggest("Mondy\tStandup", en),
+            Some(("Mondy".to_string(), "Monday"))
+        );
+        assert_eq!(
+            suggest("1 Febuary holiday\tFoo", en),
+            Some(("Febuary".to_string(), "February"))
+        );
+        assert_eq!(suggest("Tue in odd weeks\tFoo", en), None);
+        assert_eq!(suggest("1 Foo\tBar", en), None);
+        assert_eq!(suggest("25 Dec Jane birthday", en), None);
+    }
+
+    #[test]
+    fn localized() {
+        let de = Locale {
+            language: Language::German,
+            ignore_case: false,
+        };
+        assert_eq!(
+            parse_weekday(de)("Dienstag"),
+            Ok(("", time::Weekday::Tuesday))
+        );
+        assert_eq!(parse_weekday(de)("Di"), Ok(("", time::Weekday::Tuesday)));
+        assert_eq!(parse_weekday(de)("Tue"), Ok(("", time::Weekday::Tuesday)));
+        assert_eq!(parse_month(de)("Mär"), Ok(("", time::Month::March)));
+        assert_eq!(parse_month(de)("März"), Ok(("", time::Month::March)));
+        assert!(parse_month(de)("mai").is_err());
+
+        let fr = Locale {
+            language: Language::French,
+            ignore_case: true,
+        };
+        assert_eq!(parse_month(fr)("mai"), Ok(("", time::Month::May)));
+        assert_eq!(parse_month(fr)("MAI"), Ok(("", time::Month::May)));
+        assert_eq!(parse_month(fr)("juillet"), Ok(("", time::Month::July)));
+        assert_eq!(
+            parse_reminder(fr)("1 Avril"),
+            Ok(("", Reminder::Yearly(1, time::Month::April)))
+        );
+        assert_eq!(
+            parse_reminder(fr)("1 janv..mars"),
+            Ok((
+                "",
+                Reminder::YearlyList(
+                    1,
+                    vec![
+                        time::Month::January,
+                        time::Month::February,
+                        time::Month::March
+                    ]
+                )
+            ))
+        );
+        assert_eq!(
+            parse_reminder(fr)("lun+1 sept"),
+            Ok((
+                "",
+                Reminder::SemiWeeklyIn(
+                    time::Weekday::Monday,
+                    1i8.try_into().unwrap(),
+                    time::Month::September
+                )
+            ))
+        );
     }
 
     #[test]
     fn month() {
-        assert_eq!(parse_month("April"), Ok(("", time::Month::April)));
-        assert_eq!(parse_month("04"), Ok(("", time::Month::April)));
-        assert_eq!(parse_month("4"), Ok(("", time::Month::April)));
+        assert_eq!(parse_month(EN)("April"), Ok(("", time::Month::April)));
+        assert_eq!(parse_month(EN)("04"), Ok(("", time::Month::April)));
+        assert_eq!(parse_month(EN)("4"), Ok(("", time::Month::April)));
         assert_eq!(
-            parse_month("13"),
+            parse_month(EN)("13"),
             Err(Err::Error(Error::new("13", ErrorKind::MapRes)))
         );
         assert_eq!(
-            parse_month("2342"),
+            parse_month(EN)("2342"),
             Err(Err::Error(Error::new("2342", ErrorKind::MapRes)))
         );
     }
@@ -175,47 +776,222 @@ mod tests {
     #[test]
     fn reminder() {
         assert_eq!(
-            parse_reminder("25 Feb"),
+            parse_reminder(EN)("25 Feb"),
             Ok(("", Reminder::Yearly(25, time::Month::February)))
         );
         assert_eq!(
-            parse_reminder("Fri"),
+            parse_reminder(EN)("Fri"),
             Ok(("", Reminder::Weekly(time::Weekday::Friday)))
         );
         assert_eq!(
-            parse_reminder("Fri+2"),
+            parse_reminder(EN)("Fri+2"),
             Ok((
                 "",
                 Reminder::SemiWeekly(time::Weekday::Friday, 2i8.try_into().unwrap())
             )),
         );
         assert_eq!(
-            parse_reminder("Mon-4"),
+            parse_reminder(EN)("Mon-4"),
             Ok((
                 "",
                 Reminder::SemiWeekly(time::Weekday::Monday, (-4i8).try_into().unwrap())
             )),
         );
         assert_eq!(
-            parse_reminder("Jan 1990"),
+            parse_reminder(EN)("Jan 1990"),
             Ok(("", Reminder::Date(date!(1990 - 01 - 01))))
         );
         assert_eq!(
-            parse_reminder("06 July 2020"),
+            parse_reminder(EN)("06 July 2020"),
             Ok(("", Reminder::Date(date!(2020 - 07 - 06))))
         );
         assert_eq!(
-            parse_reminder("12 Dec 1950"),
+            parse_reminder(EN)("12 Dec 1950"),
             Ok(("", Reminder::Date(date!(1950 - 12 - 12))))
         );
         assert_eq!(
-            parse_reminder("10 *"),
+            parse_reminder(EN)("25 Feb 1990*"),
+            Ok(("", Reminder::Anniversary(25, time::Month::February, 1990)))
+        );
+        assert!(parse_reminder(EN)("30 Feb 1990*").is_err());
+        assert_eq!(
+            parse_reminder(EN)("10 *"),
             Ok(("", Reminder::Monthly(10, None)))
         );
         assert_eq!(
-            parse_reminder("10 * 1989"),
+            parse_reminder(EN)("10 * 1989"),
             Ok(("", Reminder::Monthly(10, Some(1989))))
         );
+        assert_eq!(
+            parse_reminder(EN)("Sun+2 May"),
+            Ok((
+                "",
+                Reminder::SemiWeeklyIn(
+                    time::Weekday::Sunday,
+                    2i8.try_into().unwrap(),
+                    time::Month::May
+                )
+            )),
+        );
+        assert_eq!(
+            parse_reminder(EN)("Mon-1 May +7"),
+            Ok((
+                "+7",
+                Reminder::SemiWeeklyIn(
+                    time::Weekday::Monday,
+                    (-1i8).try_into().unwrap(),
+                    time::Month::May
+                )
+            )),
+        );
+        assert_eq!(
+            parse_reminder(EN)("Mon,Wed,Fri"),
+            Ok((
+                "",
+                Reminder::WeeklyList(vec![
+                    time::Weekday::Monday,
+                    time::Weekday::Wednesday,
+                    time::Weekday::Friday
+                ])
+            ))
+        );
+        assert_eq!(
+            parse_reminder(EN)("Mon..Wed, Sat..Sun"),
+            Ok((
+                "",
+                Reminder::WeeklyList(vec![
+                    time::Weekday::Monday,
+                    time::Weekday::Tuesday,
+                    time::Weekday::Wednesday,
+                    time::Weekday::Saturday,
+                    time::Weekday::Sunday
+                ])
+            ))
+        );
+        assert_eq!(
+            parse_reminder(EN)("15 Jan,Apr,Jul,Oct"),
+            Ok((
+                "",
+                Reminder::YearlyList(
+                    15,
+                    vec![
+                        time::Month::January,
+                        time::Month::April,
+                        time::Month::July,
+                        time::Month::October
+                    ]
+                )
+            ))
+        );
+        assert_eq!(
+            parse_reminder(EN)("1 Nov..Feb"),
+            Ok((
+                "",
+                Reminder::YearlyList(
+                    1,
+                    vec![
+                        time::Month::November,
+                        time::Month::December,
+                        time::Month::January,
+                        time::Month::February
+                    ]
+                )
+            ))
+        );
+        assert!(parse_reminder(EN)("15 Jan,Apr 2023").is_err());
+        assert_eq!(
+            parse_reminder(EN)("Workday+3"),
+            Ok(("", Reminder::Workday(3)))
+        );
+        assert_eq!(
+            parse_reminder(EN)("Workday-1"),
+            Ok(("", Reminder::Workday(-1)))
+        );
+        assert!(parse_reminder(EN)("Workday+0").is_err());
+        assert_eq!(
+            parse_reminder(EN)("L *"),
+            Ok(("", Reminder::MonthlyFromEnd(0, None)))
+        );
+        assert_eq!(
+            parse_reminder(EN)("L-2 * 2023"),
+            Ok(("", Reminder::MonthlyFromEnd(2, Some(2023))))
+        );
+        assert!(parse_reminder(EN)("L-30 *").is_ok());
+        assert!(parse_reminder(EN)("L-31 *").is_err());
+        assert!(parse_reminder(EN)("L-40 *").is_err());
+        assert_eq!(parse_reminder(EN)("Easter"), Ok(("", Reminder::Easter)));
+        assert_eq!(
+            parse_reminder(EN)("2024-12-20"),
+            Ok(("", Reminder::Date(date!(2024 - 12 - 20))))
+        );
+        assert_eq!(
+            parse_reminder(EN)("2024-12"),
+            Ok(("", Reminder::Date(date!(2024 - 12 - 01))))
+        );
+        assert_eq!(
+            parse_reminder(EN)("--12-20"),
+            Ok(("", Reminder::Yearly(20, time::Month::December)))
+        );
+        assert_eq!(
+            parse_reminder(EN)("--02-29"),
+            Ok(("", Reminder::Yearly(29, time::Month::February)))
+        );
+        assert_eq!(
+            parse_reminder(EN)("1990-02-25*"),
+            Ok(("", Reminder::Anniversary(25, time::Month::February, 1990)))
+        );
+        assert!(parse_reminder(EN)("2023-02-29").is_err());
+        assert!(parse_reminder(EN)("--13-01").is_err());
+        assert!(parse_reminder(EN)("--1-20").is_err());
+        assert_eq!(
+            parse_reminder(EN)("15 Nisan"),
+            Ok(("", Reminder::Hebrew(15, calendars::HebrewMonth::Nisan)))
+        );
+        assert_eq!(
+            parse_reminder(EN)("14 Adar II"),
+            Ok(("", Reminder::Hebrew(14, calendars::HebrewMonth::AdarII)))
+        );
+        assert_eq!(
+            parse_reminder(EN)("1 Marcheshvan"),
+            Ok(("", Reminder::Hebrew(1, calendars::HebrewMonth::Cheshvan)))
+        );
+        assert_eq!(
+            parse_reminder(EN)("1 Ramadan"),
+            Ok(("", Reminder::Islamic(1, 9)))
+        );
+        assert_eq!(
+            parse_reminder(EN)("12 Rabi I"),
+            Ok(("", Reminder::Islamic(12, 3)))
+        );
+        assert!(parse_reminder(EN)("31 Ramadan").is_err());
+        assert!(parse_reminder(EN)("1 Avril").is_err());
+        assert!(parse_reminder(EN)("1 tishrei").is_err());
+        assert!(parse_reminder(EN)("1 ramadan").is_err());
+        assert_eq!(
+            parse_reminder(Locale::default())("1 tishri"),
+            Ok(("", Reminder::Hebrew(1, calendars::HebrewMonth::Tishrei)))
+        );
+        assert_eq!(
+            parse_reminder(Locale::default())("1 nisan"),
+            Ok(("", Reminder::Hebrew(1, calendars::HebrewMonth::Nisan)))
+        );
+        assert_eq!(
+            parse_reminder(EN)("FullMoon"),
+            Ok(("", Reminder::Astro(astro::Event::FullMoon)))
+        );
+        assert_eq!(
+            parse_reminder(EN)("MarEquinox"),
+            Ok(("", Reminder::Astro(astro::Event::MarchEquinox)))
+        );
+        assert_eq!(
+            parse_reminder(EN)("DayLength 9h30m"),
+            Ok((
+                "",
+                Reminder::DayLength(time::Duration::hours(9) + time::Duration::minutes(30), None)
+            ))
+        );
+        assert!(parse_reminder(EN)("DayLength 25h").is_err());
+        assert!(parse_reminder(EN)("DayLength 9h60m").is_err());
     }
 
     #[test]
@@ -234,23 +1010,214 @@ mod tests {
     #[test]
     fn event() {
         assert_eq!(
-            parse_entry("12 Mar 2015\tDo some stuff\n"),
+            parse_entry(EN)("12 Mar 2015\tDo some stuff\n"),
             Ok((
                 "",
                 Entry {
                     day: Reminder::Date(date!(2015 - 03 - 12)),
                     desc: "Do some stuff".to_string(),
+                    lead: 0,
+                    holiday: false,
+                    meta: Default::default(),
                 }
             ))
         );
 
         assert_eq!(
-            parse_entry("Mon\tMonday\n"),
+            parse_entry(EN)("Mon\tMonday\n"),
             Ok((
                 "",
                 Entry {
                     day: Reminder::Weekly(time::Weekday::Monday),
                     desc: "Monday".to_string(),
+                    lead: 0,
+                    holiday: false,
+                    meta: Default::default(),
+                }
+            ))
+        );
+
+        assert_eq!(
+            parse_entry(EN)("25 Dec holiday\tChristmas\n"),
+            Ok((
+                "",
+                Entry {
+                    day: Reminder::Yearly(25, time::Month::December),
+                    desc: "Christmas".to_string(),
+                    lead: 0,
+                    holiday: true,
+                    meta: Default::default(),
+                }
+            ))
+        );
+
+        assert_eq!(
+            parse_entry(EN)("25 Dec >workday holiday\tChristmas\n"),
+            Ok((
+                "",
+                Entry {
+                    day: Reminder::Shifted(
+                        Box::new(Reminder::Yearly(25, time::Month::December)),
+                        Shift::Forward
+                    ),
+                    desc: "Christmas".to_string(),
+                    lead: 0,
+                    holiday: true,
+                    meta: Default::default(),
+                }
+            ))
+        );
+
+        assert_eq!(
+            parse_entry(EN)("Sat-1 -2d +3\tBefore the last Saturday\n"),
+            Ok((
+                "",
+                Entry {
+                    day: Reminder::Offset(
+                        Box::new(Reminder::SemiWeekly(
+                            time::Weekday::Saturday,
+                            (-1i8).try_into().unwrap()
+                        )),
+                        time::Duration::days(-2)
+                    ),
+                    desc: "Before the last Saturday".to_string(),
+                    lead: 3,
+                    holiday: false,
+                    meta: Default::default(),
+                }
+            ))
+        );
+
+        assert!(parse_entry(EN)("Thu+4 Nov +1\tBlack Friday\n").is_err());
+        assert!(parse_entry(EN)("Workday+3 +2\tInvoices\n").is_err());
+        assert_eq!(ambiguous_lead("Thu+4 Nov +1\tBlack Friday", EN), Some(1));
+        assert_eq!(ambiguous_lead("Sat-1 odd years +2\tParty", EN), Some(2));
+        assert_eq!(ambiguous_lead("25 Feb +7\tBirthday", EN), None);
+
+        assert_eq!(
+            parse_entry(EN)("1 Jun every 2 years from 2022\tConference\n"),
+            Ok((
+                "",
+                Entry {
+                    day: Reminder::InYears(
+                        Box::new(Reminder::Yearly(1, time::Month::June)),
+                        YearFilter::Every(2, 2022)
+                    ),
+                    desc: "Conference".to_string(),
+                    lead: 0,
+                    holiday: false,
+                    meta: Default::default(),
+                }
+            ))
+        );
+
+        assert_eq!(
+            parse_entry(EN)("Tue odd years +1d\tWednesday\n"),
+            Ok((
+                "",
+                Entry {
+                    day: Reminder::Offset(
+                        Box::new(Reminder::InYears(
+                            Box::new(Reminder::Weekly(time::Weekday::Tuesday)),
+                            YearFilter::Odd
+                        )),
+                        time::Duration::days(1)
+                    ),
+                    desc: "Wednesday".to_string(),
+                    lead: 0,
+                    holiday: false,
+                    meta: Default::default(),
+                }
+            ))
+        );
+
+        assert_eq!(
+            parse_entry(EN)("Tue in even weeks\tGarbage collection\n"),
+            Ok((
+                "",
+                Entry {
+                    day: Reminder::InWeeks(
+                        Box::new(Reminder::Weekly(time::Weekday::Tuesday)),
+                        WeekFilter::Even
+                    ),
+                    desc: "Garbage collection".to_string(),
+                    lead: 0,
+                    holiday: false,
+                    meta: Default::default(),
+                }
+            ))
+        );
+
+        assert_eq!(
+            parse_entry(EN)("Mon..Fri in W14-W18\tOn-call\n"),
+            Ok((
+                "",
+                Entry {
+                    day: Reminder::InWeeks(
+                        Box::new(Reminder::WeeklyList(vec![
+                            time::Weekday::Monday,
+                            time::Weekday::Tuesday,
+                            time::Weekday::Wednesday,
+                            time::Weekday::Thursday,
+                            time::Weekday::Friday,
+                        ])),
+                        WeekFilter::Range(14, 18)
+                    ),
+                    desc: "On-call".to_string(),
+                    lead: 0,
+                    holiday: false,
+                    meta: Default::default(),
+                }
+            ))
+        );
+        assert!(parse_entry(EN)("Mon in W54\tInvalid\n").is_err());
+
+        assert_eq!(
+            parse_entry(EN)("29 Feb 1996* feb29=feb28\tLeapling\n"),
+            Ok((
+                "",
+                Entry {
+                    day: Reminder::LeapDay(
+                        Box::new(Reminder::Anniversary(29, time::Month::February, 1996)),
+                        Feb29::Feb28
+                    ),
+                    desc: "Leapling".to_string(),
+                    lead: 0,
+                    holiday: false,
+                    meta: Default::default(),
+                }
+            ))
+        );
+
+        assert_eq!(
+            parse_entry(EN)("Fri\tStandup #work @\"Room 1\"\n\tpriority: low\n"),
+            Ok((
+                "",
+                Entry {
+                    day: Reminder::Weekly(time::Weekday::Friday),
+                    desc: "Standup".to_string(),
+                    lead: 0,
+                    holiday: false,
+                    meta: meta::Meta {
+                        tags: vec!["work".to_string()],
+                        location: Some("Room 1".to_string()),
+                        priority: Some(meta::Priority::Low),
+                        url: None,
+                    },
+                }
+            ))
+        );
+
+        assert_eq!(
+            parse_entry(EN)("25 Feb +7\tAnna's birthday\n"),
+            Ok((
+                "",
+                Entry {
+                    day: Reminder::Yearly(25, time::Month::February),
+                    desc: "Anna's birthday".to_string(),
+                    lead: 7,
+                    holiday: false,
+                    meta: Default::default(),
                 }
             ))
         );
@@ -0,0 +1,173 @@
+//! Built-in sets of public holidays, written in the calendar file format.
+
+use crate::format::parse_entries;
+use crate::locale::Locale;
+use crate::Entry;
+
+use nom::combinator::all_consuming;
+
+// Public holidays observed in all German states.
+const DE: &str = "\
+1 Jan holiday	New Year's Day
+Easter -2d holiday	Good Friday
+Easter +1d holiday	Easter Monday
+1 May holiday	Labour Day
+Easter +39d holiday	Ascension Day
+Easter +50d holiday	Whit Monday
+3 Oct holiday	German Unity Day
+25 Dec holiday	Christmas Day
+26 Dec holiday	Second Day of Christmas
+";
+
+const EPIPHANY: &str = "6 Jan holiday\tEpiphany\n";
+const WOMENS_DAY: &str = "8 Mar holiday\tInternational Women's Day\n";
+const CORPUS_CHRISTI: &str = "Easter +60d holiday\tCorpus Christi\n";
+const ASSUMPTION: &str = "15 Aug holiday\tAssumption Day\n";
+const REFORMATION: &str = "31 Oct holiday\tReformation Day\n";
+const ALL_SAINTS: &str = "1 Nov holiday\tAll Saints' Day\n";
+
+// Federal holidays of the United States (without observed days).
+const US: &str = "\
+1 Jan holiday	New Year's Day
+Mon+3 Jan holiday	Martin Luther King Jr. Day
+Mon+3 Feb holiday	Washington's Birthday
+Mon-1 May holiday	Memorial Day
+19 Jun holiday	Juneteenth
+4 Jul holiday	Independence Day
+Mon+1 Sep holiday	Labor Day
+Mon+2 Oct holiday	Columbus Day
+11 Nov holiday	Veterans Day
+Thu+4 Nov holiday	Thanksgiving Day
+25 Dec holiday	Christmas Day
+";
+
+/// Names of all holiday sets with their entries.
+pub const SETS: &[(&str, &[&str])] = &[
+    ("de", &[DE]),
+    ("de-BW", &[DE, EPIPHANY, CORPUS_CHRISTI, ALL_SAINTS]),
+    (
+        "de-BY",
+        &[DE, EPIPHANY, CORPUS_CHRISTI, ASSUMPTION, ALL_SAINTS],
+    ),
+    ("de-BE", &[DE, WOMENS_DAY]),
+    (
+        "de-BB",
+        &[
+            DE,
+            "Easter holiday\tEaster Sunday\nEaster +49d holiday\tWhit Sunday\n",
+            REFORMATION,
+        ],
+    ),
+    ("de-HB", &[DE, REFORMATION]),
+    ("de-HH", &[DE, REFORMATION]),
+    ("de-HE", &[DE, CORPUS_CHRISTI]),
+    ("de-MV", &[DE, WOMENS_DAY, REFORMATION]),
+    ("de-NI", &[DE, REFORMATION]),
+    ("de-NW", &[DE, CORPUS_CHRISTI, ALL_SAINTS]),
+    ("de-RP", &[DE, CORPUS_CHRISTI, ALL_SAINTS]),
+    ("de-SL", &[DE, CORPUS_CHRISTI, ASSUMPTION, ALL_SAINTS]),
+    (
+        "de-SN",
+        &[
+            DE,
+            REFORMATION,
+            "Tue+3 Nov +1d holiday\tDay of Repentance and Prayer\n",
+        ],
+    ),
+    ("de-ST", &[DE, EPIPHANY, REFORMATION]),
+    ("de-SH", &[DE, REFORMATION]),
+    (
+        "de-TH",
+        &[DE, "20 Sep holiday\tWorld Children's Day\n", REFORMATION],
+    ),
+    ("us", &[US]),
+];
+
+/// Whether a holiday set with the given name exists.
+pub fn exists(name: &str) -> bool {
+    SETS.iter().any(|(n, _)| *n == name)
+}
+
+/// Returns the entries of the given holiday sets. Each description is
+/// marked with the name of the set, holidays which are part of multiple
+/// sets are only included once.
+pub fn entries(names: &[String]) -> Vec<Entry> {
+    let mut out: Vec<(Entry, &str)> = Vec::new();
+    for (name, parts) in SETS.iter().filter(|(n, _)| names.iter().any(|m| m == n)) {
+        for part in parts.iter() {
+            // The built-in sets are covered by tests and always parse.
+            let (_, entries) =
+                all_consuming(parse_entries(Locale::default()))(part).expect("invalid holiday set");
+            for entry in entries {
+                if !out.iter().any(|(e, _)| *e == entry) {
+                    out.push((entry, name));
+                }
+            }
+        }
+    }
+
+    out.into_iter()
+        .map(|(entry, name)| Entry {
+            desc: format!("{} ({})", entry.desc, name),
+            ..entry
+        })
+        .collect()
+}
+
+/// Returns the date of Easter Sunday in the given year of the Gregorian
+/// calendar, computed using the anonymous Gregorian algorithm.
+pub fn easter(year: crate::Year) -> Option<time::Date> {
+    let a = year.rem_euclid(19);
+    let (b, c) = (year.div_euclid(100), year.rem_euclid(100));
+    let (d, e) = (b.div_euclid(4), b.rem_euclid(4));
+    let f = (b + 8).div_euclid(25);
+    let g = (b - f + 1).div_euclid(3);
+    let h = (19 * a + b - d - g + 15).rem_euclid(30);
+    let (i, k) = (c / 4, c % 4);
+    let l = (32 + 2 * e + 2 * i - h - k).rem_euclid(7);
+    let m = (a + 11 * h + 22 * l) / 451;
+    let n = h + l - 7 * m + 114;
+
+    let month = time::Month::try_from((n / 31) as u8).ok()?;
+    time::Date::from_calendar_date(year, month, (n % 31 + 1) as u8).ok()
+}
+
+////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::date;
+
+    #[test]
+    fn easter_sunday() {
+        assert_eq!(easter(2024), Some(date!(2024 - 03 - 31)));
+        assert_eq!(easter(2025), Some(date!(2025 - 04 - 20)));
+        assert_eq!(easter(2038), Some(date!(2038 - 04 - 25)));
+        assert_eq!(easter(1818), Some(date!(1818 - 03 - 22)));
+    }
+
+    #[test]
+    fn sets() {
+        for (name, _) in SETS {
+            assert!(!entries(&[name.to_string()]).is_empty());
+        }
+
+        let de = entries(&["de".to_string()]);
+        assert_eq!(de.len(), 9);
+        assert!(de.iter().all(|e| e.holiday && e.desc.ends_with(" (de)")));
+
+        // Christmas Day and New Year's Day are only included once.
+        let names = ["de-BY".to_string(), "us".to_string()];
+        let both = entries(&names);
+        assert_eq!(both.len(), 9 + 4 + 9);
+        assert_eq!(both[0].desc, "New Year's Day (de-BY)");
+
+        // Day of Repentance and Prayer: Wednesday before 23rd November
+        let sn = entries(&["de-SN".to_string()]);
+        let day = &sn.last().unwrap().day;
+        assert!(day.matches(date!(2023 - 11 - 22)));
+        assert!(day.matches(date!(2024 - 11 - 20)));
+        assert!(!day.matches(date!(2023 - 11 - 15)));
+    }
+}
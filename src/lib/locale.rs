@@ -0,0 +1,382 @@
+//! Names of weekdays and months in different languages.
+
+/// Languages with built-in names for weekdays and months.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Language {
+    English,
+    German,
+    French,
+    Spanish,
+    Dutch,
+}
+
+struct Names {
+    weekdays: [&'static str; 7], // Starting with Monday
+    weekdays_short: [&'static str; 7],
+    months: [&'static str; 12],
+    months_short: [&'static str; 12],
+}
+
+const EN: Names = Names {
+    weekdays: [
+        "Monday",
+        "Tuesday",
+        "Wednesday",
+        "Thursday",
+        "Friday",
+        "Saturday",
+        "Sunday",
+    ],
+    weekdays_short: ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"],
+    months: [
+        "January",
+        "February",
+        "March",
+        "April",
+        "May",
+        "June",
+        "July",
+        "August",
+        "September",
+        "October",
+        "November",
+        "December",
+    ],
+    months_short: [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ],
+};
+
+const DE: Names = Names {
+    weekdays: [
+        "Montag",
+        "Dienstag",
+        "Mittwoch",
+        "Donnerstag",
+        "Freitag",
+        "Samstag",
+        "Sonntag",
+    ],
+    weekdays_short: ["Mo", "Di", "Mi", "Do", "Fr", "Sa", "So"],
+    months: [
+        "Januar",
+        "Februar",
+        "März",
+        "April",
+        "Mai",
+        "Juni",
+        "Juli",
+        "August",
+        "September",
+        "Oktober",
+        "November",
+        "Dezember",
+    ],
+    months_short: [
+        "Jan", "Feb", "Mär", "Apr", "Mai", "Jun", "Jul", "Aug", "Sep", "Okt", "Nov", "Dez",
+    ],
+};
+
+const FR: Names = Names {
+    weekdays: [
+        "lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche",
+    ],
+    weekdays_short: ["lun", "mar", "mer", "jeu", "ven", "sam", "dim"],
+    months: [
+        "janvier",
+        "février",
+        "mars",
+        "avril",
+        "mai",
+        "juin",
+        "juillet",
+        "août",
+        "septembre",
+        "octobre",
+        "novembre",
+        "décembre",
+    ],
+    months_short: [
+        "janv", "févr", "mars", "avr", "mai", "juin", "juil", "août", "sept", "oct", "nov", "déc",
+    ],
+};
+
+const ES: Names = Names {
+    weekdays: [
+        "lunes",
+        "martes",
+        "miércoles",
+        "jueves",
+        "viernes",
+        "sábado",
+        "domingo",
+    ],
+    weekdays_short: ["lun", "mar", "mié", "jue", "vie", "sáb", "dom"],
+    months: [
+        "enero",
+        "febrero",
+        "marzo",
+        "abril",
+        "mayo",
+        "junio",
+        "julio",
+        "agosto",
+        "septiembre",
+        "octubre",
+        "noviembre",
+        "diciembre",
+    ],
+    months_short: [
+        "ene", "feb", "mar", "abr", "may", "jun", "jul", "ago", "sep", "oct", "nov", "dic",
+    ],
+};
+
+const NL: Names = Names {
+    weekdays: [
+        "maandag",
+        "dinsdag",
+        "woensdag",
+        "donderdag",
+        "vrijdag",
+        "zaterdag",
+        "zondag",
+    ],
+    weekdays_short: ["ma", "di", "wo", "do", "vr", "za", "zo"],
+    months: [
+        "januari",
+        "februari",
+        "maart",
+        "april",
+        "mei",
+        "juni",
+        "juli",
+        "augustus",
+        "september",
+        "oktober",
+        "november",
+        "december",
+    ],
+    months_short: [
+        "jan", "feb", "mrt", "apr", "mei", "jun", "jul", "aug", "sep", "okt", "nov", "dec",
+    ],
+};
+
+impl Language {
+    /// Returns the language for an ISO 639-1 code or a locale name like
+    /// `de_DE.UTF-8` (as used in the `LANG` environment variable).
+    pub fn from_code(code: &str) -> Option<Self> {
+        let code = code.split(['_', '.', '-']).next().unwrap_or("");
+        match code.to_lowercase().as_str() {
+            "en" => Some(Language::English),
+            "de" => Some(Language::German),
+            "fr" => Some(Language::French),
+            "es" => Some(Language::Spanish),
+            "nl" => Some(Language::Dutch),
+            _ => None,
+        }
+    }
+
+    fn names(&self) -> &'static Names {
+        match self {
+            Language::English => &EN,
+            Language::German => &DE,
+            Language::French => &FR,
+            Language::Spanish => &ES,
+            Language::Dutch => &NL,
+        }
+    }
+}
+
+const WEEKDAYS: [time::Weekday; 7] = [
+    time::Weekday::Monday,
+    time::Weekday::Tuesday,
+    time::Weekday::Wednesday,
+    time::Weekday::Thursday,
+    time::Weekday::Friday,
+    time::Weekday::Saturday,
+    time::Weekday::Sunday,
+];
+
+const MONTHS: [time::Month; 12] = [
+    time::Month::January,
+    time::Month::February,
+    time::Month::March,
+    time::Month::April,
+    time::Month::May,
+    time::Month::June,
+    time::Month::July,
+    time::Month::August,
+    time::Month::September,
+    time::Month::October,
+    time::Month::November,
+    time::Month::December,
+];
+
+/// Determines the names of weekdays and months for input and output.
+/// English names are accepted in the input regardless of the language.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Locale {
+    pub language: Language,
+    pub ignore_case: bool,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Locale {
+            language: Language::English,
+            ignore_case: true,
+        }
+    }
+}
+
+// Common abbreviations which are not a prefix of the full name.
+const WEEKDAY_ALIASES: [(&str, time::Weekday); 1] = [("Weds", time::Weekday::Wednesday)];
+
+// Amount of single character edits needed to turn one string into another.
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cur = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                prev
+            } else {
+                1 + prev.min(row[j]).min(cur)
+            };
+            prev = cur;
+        }
+    }
+
+    row[b.len()]
+}
+
+/// Find the value of the given name, which is either one of the given
+/// names or an unambiguous prefix of at least three letters.
+pub(crate) fn find_name<T: Copy + PartialEq>(
+    word: &str,
+    names: impl Iterator<Item = (&'static str, T)> + Clone,
+    ignore_case: bool,
+) -> Option<T> {
+    let (word, fold) = if ignore_case {
+        (word.to_lowercase(), str::to_lowercase as fn(&str) -> String)
+    } else {
+        (word.to_string(), str::to_string as fn(&str) -> String)
+    };
+
+    let exact = names.clone().find(|(name, _)| fold(name) == word);
+    exact.map(|(_, val)| val).or_else(|| {
+        let mut vals = names
+            .filter(|(name, _)| word.chars().count() >= 3 && fold(name).starts_with(&word))
+            .map(|(_, val)| val);
+        let first = vals.next()?;
+        vals.all(|v| v == first).then_some(first)
+    })
+}
+
+impl Locale {
+    /// Returns the weekday with the given name, accepting the same names
+    /// and abbreviations as calendar files.
+    pub fn weekday_by_name(&self, name: &str) -> Option<time::Weekday> {
+        find_name(name, self.weekdays(), self.ignore_case)
+    }
+
+    pub fn weekday(&self, wday: time::Weekday) -> &'static str {
+        self.language.names().weekdays[wday.number_days_from_monday() as usize]
+    }
+
+    pub fn weekday_short(&self, wday: time::Weekday) -> &'static str {
+        self.language.names().weekdays_short[wday.number_days_from_monday() as usize]
+    }
+
+    pub fn month_short(&self, mon: time::Month) -> &'static str {
+        self.language.names().months_short[mon as usize - 1]
+    }
+
+    // Names of the language and the English names, each with the
+    // full name before the abbreviation.
+    fn all_names(&self) -> impl Iterator<Item = &'static Names> + Clone {
+        let names = self.language.names();
+        std::iter::once(names).chain(Some(&EN).filter(|en| !std::ptr::eq(*en, names)))
+    }
+
+    /// All accepted names of weekdays.
+    pub(crate) fn weekdays(&self) -> impl Iterator<Item = (&'static str, time::Weekday)> + Clone {
+        self.all_names()
+            .flat_map(|n| {
+                let full = n.weekdays.iter().zip(WEEKDAYS);
+                let short = n.weekdays_short.iter().zip(WEEKDAYS);
+                full.chain(short).map(|(name, wday)| (*name, wday))
+            })
+            .chain(WEEKDAY_ALIASES)
+    }
+
+    /// All accepted names of months.
+    pub(crate) fn months(&self) -> impl Iterator<Item = (&'static str, time::Month)> + Clone {
+        self.all_names().flat_map(|n| {
+            let full = n.months.iter().zip(MONTHS);
+            let short = n.months_short.iter().zip(MONTHS);
+            full.chain(short).map(|(name, mon)| (*name, mon))
+        })
+    }
+
+    /// Returns the name of a weekday or month which is similar to the
+    /// given word, i.e. which differs by one or two characters.
+    pub fn suggest(&self, word: &str) -> Option<&'static str> {
+        let word = word.to_lowercase();
+        let max = word.chars().count() / 3;
+
+        let weekdays = self.weekdays().map(|(name, _)| name);
+        let months = self.months().map(|(name, _)| name);
+        weekdays
+            .chain(months)
+            .map(|name| (distance(&word, &name.to_lowercase()), name))
+            .min_by_key(|(d, _)| *d)
+            .filter(|(d, _)| *d > 0 && *d <= max.min(2))
+            .map(|(_, name)| name)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn language() {
+        assert_eq!(Language::from_code("de_DE.UTF-8"), Some(Language::German));
+        assert_eq!(Language::from_code("nl"), Some(Language::Dutch));
+        assert_eq!(Language::from_code("fr-CA"), Some(Language::French));
+        assert_eq!(Language::from_code("C"), None);
+    }
+
+    #[test]
+    fn suggestions() {
+        let en = Locale::default();
+        assert_eq!(en.suggest("Thrusday"), Some("Thursday"));
+        assert_eq!(en.suggest("Febuary"), Some("February"));
+        assert_eq!(en.suggest("Mondy"), Some("Monday"));
+        assert_eq!(en.suggest("odd"), None);
+        assert_eq!(en.suggest("Monday"), None);
+        assert_eq!(en.weekday_by_name("thurs"), Some(time::Weekday::Thursday));
+        assert_eq!(en.weekday_by_name("t"), None);
+        assert_eq!(distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn output() {
+        let de = Locale {
+            language: Language::German,
+            ignore_case: false,
+        };
+        assert_eq!(de.weekday_short(time::Weekday::Tuesday), "Di");
+        assert_eq!(de.month_short(time::Month::March), "Mär");
+
+        let en = Locale::default();
+        assert_eq!(en.weekday_short(time::Weekday::Sunday), "Sun");
+        assert_eq!(en.month_short(time::Month::December), "Dec");
+    }
+}
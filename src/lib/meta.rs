@@ -0,0 +1,227 @@
+use std::str::FromStr;
+
+/// Priority of an entry, ordered from low to high.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    Low,
+    Medium,
+    High,
+}
+
+impl FromStr for Priority {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "low" => Ok(Priority::Low),
+            "medium" => Ok(Priority::Medium),
+            "high" => Ok(Priority::High),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Optional structured information about an entry.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Meta {
+    pub tags: Vec<String>,
+    pub location: Option<String>,
+    pub priority: Option<Priority>,
+    pub url: Option<String>,
+}
+
+impl Meta {
+    pub fn is_empty(&self) -> bool {
+        *self == Meta::default()
+    }
+
+    // Apply a metadata line of the form `key: value`, returns false if
+    // the line does not contain metadata.
+    fn set(&mut self, line: &str) -> bool {
+        let (key, val) = match line.split_once(':') {
+            Some((key, val)) => (key.trim(), val.trim()),
+            None => return false,
+        };
+
+        match key {
+            "tags" => self.tags.extend(
+                val.split(',')
+                    .map(str::trim)
+                    .filter(|t| !t.is_empty())
+                    .map(str::to_string),
+            ),
+            "location" => self.location = Some(val.to_string()),
+            "priority" => match val.parse() {
+                Ok(p) => self.priority = Some(p),
+                Err(_) => return false,
+            },
+            "url" => self.url = Some(val.to_string()),
+            _ => return false,
+        }
+
+        true
+    }
+}
+
+// Whether the given word is an inline tag, priority or URL.
+fn is_marker(word: &str) -> bool {
+    let mut chars = word.chars();
+    match (chars.next(), chars.next()) {
+        (Some('#'), Some(c)) => c.is_alphabetic(),
+        (Some('!'), _) => word[1..].parse::<Priority>().is_ok(),
+        (Some('<'), _) => word.ends_with('>') && word.contains("://"),
+        _ => false,
+    }
+}
+
+// Extract inline metadata from a single line of the description. A
+// location is either quoted (`@"Coffee Place"`) or a single word at the
+// end of the line (`@Office`), otherwise the `@` is part of the text.
+fn extract_inline(line: &str, meta: &mut Meta) -> String {
+    let words: Vec<&str> = line.split(' ').collect();
+    let mut text = Vec::new();
+
+    let mut idx = 0;
+    while idx < words.len() {
+        let word = words[idx];
+        idx += 1;
+
+        if let Some(quoted) = word.strip_prefix("@\"") {
+            // Number of following words up to the closing quote.
+            let rest = &words[idx..];
+            let len = match quoted.ends_with('"') {
+                true => Some(0),
+                false => rest.iter().position(|w| w.ends_with('"')).map(|n| n + 1),
+            };
+
+            if let Some(len) = len {
+                let loc = std::iter::once(quoted).chain(rest[..len].iter().copied());
+                let loc = loc.collect::<Vec<_>>().join(" ");
+                let loc = loc.trim_end_matches('"');
+                if !loc.is_empty() {
+                    meta.location = Some(loc.to_string());
+                    idx += len;
+                    continue;
+                }
+            }
+        }
+
+        // An unquoted location is a single word at the end of the line,
+        // only followed by other markers.
+        let trailing = words[idx..].iter().all(|w| w.is_empty() || is_marker(w));
+        if word.len() > 1 && word.starts_with('@') && !word.starts_with("@\"") && trailing {
+            meta.location = Some(word[1..].to_string());
+            continue;
+        }
+
+        if !is_marker(word) {
+            text.push(word);
+            continue;
+        }
+        match word.split_at(1) {
+            ("#", tag) => meta.tags.push(tag.to_string()),
+            ("!", prio) => meta.priority = prio.parse().ok(),
+            (_, url) => meta.url = Some(url[..url.len() - 1].to_string()),
+        }
+    }
+
+    text.join(" ").trim_end().to_string()
+}
+
+/// Split the given description into the text and its metadata. Metadata
+/// is either given inline in the first line (`#tag`, `@location`,
+/// `!priority` and `<url>`) or on continuation lines (`key: value`).
+pub fn extract(desc: &str) -> (String, Meta) {
+    let mut meta = Meta::default();
+    let mut lines = desc.split("\n\t");
+
+    let mut out = vec![extract_inline(lines.next().unwrap_or(""), &mut meta)];
+    out.extend(lines.filter(|line| !meta.set(line)).map(str::to_string));
+
+    (out.join("\n\t"), meta)
+}
+
+////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inline() {
+        let (desc, meta) =
+            extract("Meeting with Hannah #work !high <https://example.org> @\"Coffee Place\"");
+        assert_eq!(desc, "Meeting with Hannah");
+        assert_eq!(
+            meta,
+            Meta {
+                tags: vec!["work".to_string()],
+                location: Some("Coffee Place".to_string()),
+                priority: Some(Priority::High),
+                url: Some("https://example.org".to_string()),
+            }
+        );
+
+        let (desc, meta) = extract("Call Bob @ 5pm! #1 and !urgent");
+        assert_eq!(desc, "Call Bob @ 5pm! #1 and !urgent");
+        assert!(meta.is_empty());
+    }
+
+    #[test]
+    fn quoted_location() {
+        let (desc, meta) = extract("Lunch @\"Coffee Place\" with Hannah #private");
+        assert_eq!(desc, "Lunch with Hannah");
+        assert_eq!(meta.location, Some("Coffee Place".to_string()));
+        assert_eq!(meta.tags, vec!["private"]);
+
+        let (desc, meta) = extract("Standup @\"Room 1\"");
+        assert_eq!(desc, "Standup");
+        assert_eq!(meta.location, Some("Room 1".to_string()));
+
+        let (desc, meta) = extract("Standup @Office #work");
+        assert_eq!(desc, "Standup");
+        assert_eq!(meta.location, Some("Office".to_string()));
+
+        let (desc, meta) = extract("Quote @\"unterminated");
+        assert_eq!(desc, "Quote @\"unterminated");
+        assert!(meta.is_empty());
+    }
+
+    #[test]
+    fn plain() {
+        for desc in [
+            "Read <Dune> with @alice tonight",
+            "Mail bob@example.org about <foo>",
+            "Ask @bob #1 question",
+            "C# workshop",
+            "Tickets <3",
+        ] {
+            let (out, meta) = extract(desc);
+            assert_eq!(out, desc);
+            assert!(meta.is_empty());
+        }
+
+        // Only the tag is removed, the rest of the text is unchanged.
+        let (desc, meta) = extract("Read <Dune> with @alice tonight #book");
+        assert_eq!(desc, "Read <Dune> with @alice tonight");
+        assert_eq!(meta.tags, vec!["book"]);
+        assert_eq!(meta.location, None);
+        assert_eq!(meta.url, None);
+    }
+
+    #[test]
+    fn continuation() {
+        let (desc, meta) =
+            extract("Dentist\n\tBring the card\n\tlocation: Main St 1\n\ttags: health, private");
+        assert_eq!(desc, "Dentist\n\tBring the card");
+        assert_eq!(meta.location, Some("Main St 1".to_string()));
+        assert_eq!(meta.tags, vec!["health", "private"]);
+
+        let (_, meta) = extract("Dentist\n\ttags: health,\n\ttags:");
+        assert_eq!(meta.tags, vec!["health"]);
+
+        let (desc, meta) = extract("Dentist\n\tNote: priority: none");
+        assert_eq!(desc, "Dentist\n\tNote: priority: none");
+        assert!(meta.is_empty());
+    }
+}
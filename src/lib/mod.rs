@@ -1,21 +1,36 @@
 extern crate nom;
 extern crate time;
 
+pub mod astro;
+pub mod calendars;
+pub mod config;
 mod cpp;
 pub mod error;
 mod format;
+pub mod holidays;
+pub mod locale;
+pub mod meta;
+mod subst;
+pub mod sun;
 mod util;
 mod weekday;
+mod workday;
 
 use std::convert;
+use std::fs;
 use std::path;
 
+use crate::config::Config;
 use crate::error::Error;
 use crate::format::*;
+use crate::locale::Locale;
+
+use time::macros::format_description;
+
+pub use crate::workday::Workdays;
 
 ////////////////////////////////////////////////////////////////////////
 
-///
 pub type Day = u8; // Day of the month
 pub type Year = i32;
 
@@ -43,7 +58,7 @@ impl TryFrom<usize> for WeekOffsetAmount {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct WeekOffset {
     // Whether the offset is relative to the start or the end of the month.
     from_start: bool,
@@ -56,12 +71,12 @@ impl TryFrom<i8> for WeekOffset {
     type Error = ();
 
     fn try_from(v: i8) -> Result<Self, Self::Error> {
-        let amount: usize = v.abs() as usize;
+        let amount: usize = v.unsigned_abs().into();
         let amount: WeekOffsetAmount = amount.try_into()?;
 
         Ok(WeekOffset {
             from_start: v > 0,
-            amount: amount,
+            amount,
         })
     }
 }
@@ -86,20 +101,104 @@ impl WeekOffset {
     }
 }
 
-///
-#[derive(Debug, PartialEq)]
+/// Direction in which dates are moved if they are not working days.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Shift {
+    Forward,  // Next working day
+    Backward, // Previous working day
+}
+
+/// Restricts a reminder to certain years.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum YearFilter {
+    Every(u32, Year), // Every N years starting with the given year
+    Odd,
+    Even,
+    Leap,
+}
+
+impl YearFilter {
+    pub fn matches(&self, year: Year) -> bool {
+        match self {
+            YearFilter::Every(n, from) => year >= *from && (year - from) % (*n as Year) == 0,
+            YearFilter::Odd => year % 2 != 0,
+            YearFilter::Even => year % 2 == 0,
+            YearFilter::Leap => time::util::is_leap_year(year),
+        }
+    }
+}
+
+/// Restricts a reminder to certain ISO 8601 weeks.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum WeekFilter {
+    Odd,
+    Even,
+    Range(u8, u8), // Inclusive range of week numbers
+}
+
+impl WeekFilter {
+    pub fn matches(&self, date: time::Date) -> bool {
+        let week = date.iso_week();
+        match *self {
+            WeekFilter::Odd => !week.is_multiple_of(2),
+            WeekFilter::Even => week.is_multiple_of(2),
+            WeekFilter::Range(start, end) if start <= end => start <= week && week <= end,
+            // Range wraps around the end of the year (e.g. W52-W02).
+            WeekFilter::Range(start, end) => week >= start || week <= end,
+        }
+    }
+}
+
+/// Determines on which day yearly reminders on the 29th February
+/// take place in years which are not leap years.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Feb29 {
+    Feb28,
+    Mar1,
+    Skip,
+}
+
+/// Describes on which days an entry takes place.
+#[derive(Clone, Debug, PartialEq)]
 pub enum Reminder {
     Weekly(time::Weekday),
+    WeeklyList(Vec<time::Weekday>),
     SemiWeekly(time::Weekday, WeekOffset),
+    SemiWeeklyIn(time::Weekday, WeekOffset, time::Month), // Only in the given month
     Monthly(Day, Option<Year>),
+    MonthlyClamped(Day, Option<Year>), // Last day if the day does not exist
+    MonthlyFromEnd(Day, Option<Year>), // Days before the last day of the month
     Yearly(Day, time::Month),
+    YearlyList(Day, Vec<time::Month>),
+    Anniversary(Day, time::Month, Year), // Yearly after the given year
+    Workday(i8), // Nth working day from the start (positive) or end (negative) of the month
     Date(time::Date),
+    Shifted(Box<Reminder>, Shift),         // Moved to a working day
+    Offset(Box<Reminder>, time::Duration), // Days after (or before) the inner reminder
+    InYears(Box<Reminder>, YearFilter),
+    InWeeks(Box<Reminder>, WeekFilter),
+    LeapDay(Box<Reminder>, Feb29), // Moves the 29th February in non-leap years
+    Easter,                        // Easter Sunday in the Gregorian calendar
+    Hebrew(Day, calendars::HebrewMonth),
+    Islamic(Day, u8), // Day and month of the tabular Islamic calendar
+    Julian(Day, time::Month),
+    Astro(astro::Event),
+    DayLength(time::Duration, Option<sun::Location>), // Day length reaches or falls below the duration
 }
 
 impl Reminder {
+    /// Whether the reminder takes place on the given date, assuming
+    /// a weekend from Saturday to Sunday and no holidays.
     pub fn matches(&self, date: time::Date) -> bool {
+        self.matches_on(date, &Workdays::default())
+    }
+
+    /// Whether the reminder takes place on the given date, using the
+    /// given working days for reminders which depend on them.
+    pub fn matches_on(&self, date: time::Date, days: &Workdays) -> bool {
         match self {
             Reminder::Weekly(wday) => date.weekday() == *wday,
+            Reminder::WeeklyList(wdays) => wdays.contains(&date.weekday()),
             Reminder::SemiWeekly(wday, off) => weekday::filter(date.year(), date.month(), *wday)
                 .map(|wdays| -> bool {
                     if date.weekday() != *wday {
@@ -109,13 +208,272 @@ impl Reminder {
                     }
                 })
                 .unwrap_or(false),
+            Reminder::SemiWeeklyIn(wday, off, mon) => {
+                date.month() == *mon && Reminder::SemiWeekly(*wday, *off).matches_on(date, days)
+            }
             Reminder::Monthly(day, year) => {
                 date.day() == *day && year.map(|y| date.year() == y).unwrap_or(true)
             }
+            Reminder::MonthlyClamped(day, year) => {
+                let last = date.month().length(date.year());
+                date.day() == (*day).min(last) && year.map(|y| date.year() == y).unwrap_or(true)
+            }
+            Reminder::MonthlyFromEnd(off, year) => {
+                let last = date.month().length(date.year());
+                last > *off
+                    && date.day() == last - off
+                    && year.map(|y| date.year() == y).unwrap_or(true)
+            }
             Reminder::Yearly(day, mon) => date.month() == *mon && date.day() == *day,
+            Reminder::YearlyList(day, mons) => mons.contains(&date.month()) && date.day() == *day,
+            Reminder::Anniversary(day, mon, year) => {
+                date.month() == *mon && date.day() == *day && date.year() > *year
+            }
+            Reminder::Workday(n) => {
+                let wdays = days.filter(date.year(), date.month());
+                let idx = if *n > 0 {
+                    usize::from(n.unsigned_abs()) - 1
+                } else {
+                    match wdays.len().checked_sub(n.unsigned_abs().into()) {
+                        Some(idx) => idx,
+                        None => return false,
+                    }
+                };
+
+                wdays.get(idx) == Some(&date)
+            }
             Reminder::Date(d) => date == *d,
+            Reminder::Easter => holidays::easter(date.year()) == Some(date),
+            Reminder::Hebrew(day, mon) => calendars::is_hebrew_date(date, *day, *mon),
+            Reminder::Islamic(day, mon) => calendars::is_islamic_date(date, *day, *mon),
+            Reminder::Julian(day, mon) => calendars::is_julian_date(date, *day, *mon),
+            Reminder::Astro(event) => event.matches(date),
+            Reminder::DayLength(len, Some(loc)) => date
+                .previous_day()
+                .map(|prev| {
+                    (sun::day_length(prev, loc) < *len) != (sun::day_length(date, loc) < *len)
+                })
+                .unwrap_or(false),
+            Reminder::DayLength(_, None) => false,
+            Reminder::LeapDay(inner, policy) => {
+                if inner.matches_on(date, days) {
+                    return true;
+                } else if time::util::is_leap_year(date.year()) {
+                    return false;
+                }
+
+                let day = match policy {
+                    Feb29::Feb28 => (time::Month::February, 28),
+                    Feb29::Mar1 => (time::Month::March, 1),
+                    Feb29::Skip => return false,
+                };
+                (date.month(), date.day()) == day && inner.matches_feb29(date.year())
+            }
+            Reminder::InWeeks(inner, filter) => {
+                filter.matches(date) && inner.matches_on(date, days)
+            }
+            Reminder::InYears(inner, filter) => {
+                filter.matches(date.year()) && inner.matches_on(date, days)
+            }
+            Reminder::Offset(inner, off) => date
+                .checked_sub(*off)
+                .map(|d| inner.matches_on(d, days))
+                .unwrap_or(false),
+            Reminder::Shifted(inner, shift) => Self::unshifted(inner, *shift, date, days).is_some(),
         }
     }
+
+    // Returns the date of the occurrence of the inner reminder which is
+    // moved to the given date by the shift, which may be the date itself.
+    fn unshifted(
+        inner: &Reminder,
+        shift: Shift,
+        date: time::Date,
+        days: &Workdays,
+    ) -> Option<time::Date> {
+        if !days.is_workday(date) {
+            return None;
+        } else if inner.matches_on(date, days) {
+            return Some(date);
+        }
+
+        // Check if any of the adjacent non-working days which are
+        // moved to the given date is matched by the inner reminder.
+        let mut day = Some(date);
+        for _ in 0..366 {
+            day = match shift {
+                Shift::Forward => day.and_then(|d| d.previous_day()),
+                Shift::Backward => day.and_then(|d| d.next_day()),
+            };
+
+            match day {
+                Some(d) if !days.is_workday(d) => {
+                    if inner.matches_on(d, days) {
+                        return Some(d);
+                    }
+                }
+                _ => break,
+            }
+        }
+
+        None
+    }
+
+    // Whether the reminder would take place on the 29th February if the
+    // given year had one. Only supported for yearly reminders.
+    fn matches_feb29(&self, year: Year) -> bool {
+        match self {
+            Reminder::Yearly(29, time::Month::February) => true,
+            Reminder::YearlyList(29, mons) => mons.contains(&time::Month::February),
+            Reminder::Anniversary(29, time::Month::February, origin) => year > *origin,
+            _ => false,
+        }
+    }
+
+    /// Returns the innermost reminder which is modified by all
+    /// modifiers (e.g. year filters or offsets) of this reminder.
+    pub fn base(&self) -> &Reminder {
+        match self {
+            Reminder::Shifted(inner, _)
+            | Reminder::Offset(inner, _)
+            | Reminder::InYears(inner, _)
+            | Reminder::InWeeks(inner, _) => inner.base(),
+            _ => self,
+        }
+    }
+
+    /// Mutable variant of [`Reminder::base`].
+    pub fn base_mut(&mut self) -> &mut Reminder {
+        match self {
+            Reminder::Shifted(inner, _)
+            | Reminder::Offset(inner, _)
+            | Reminder::InYears(inner, _)
+            | Reminder::InWeeks(inner, _) => inner.base_mut(),
+            _ => self,
+        }
+    }
+
+    /// Whether the reminder refers to the 29th February without a policy
+    /// for other years, i.e. it is silently skipped in common years.
+    pub fn skips_feb29(&self) -> bool {
+        match self {
+            Reminder::Shifted(inner, _)
+            | Reminder::Offset(inner, _)
+            | Reminder::InYears(inner, _)
+            | Reminder::InWeeks(inner, _) => inner.skips_feb29(),
+            _ => self.matches_feb29(Year::MAX),
+        }
+    }
+
+    /// Apply the given policy for the 29th February, if the reminder
+    /// refers to that day and doesn't specify a policy already.
+    pub fn set_feb29(&mut self, policy: Feb29) {
+        let base = self.base_mut();
+        if base.matches_feb29(Year::MAX) {
+            *base = Reminder::LeapDay(Box::new(base.clone()), policy);
+        }
+    }
+
+    fn is_fixed(&self) -> bool {
+        match self {
+            Reminder::Shifted(inner, _)
+            | Reminder::Offset(inner, _)
+            | Reminder::InYears(inner, _)
+            | Reminder::InWeeks(inner, _)
+            | Reminder::LeapDay(inner, _) => inner.is_fixed(),
+            _ => !matches!(
+                self,
+                Reminder::SemiWeekly(_, _)
+                    | Reminder::SemiWeeklyIn(_, _, _)
+                    | Reminder::Weekly(_)
+                    | Reminder::WeeklyList(_)
+                    | Reminder::Monthly(_, _)
+                    | Reminder::MonthlyClamped(_, _)
+                    | Reminder::MonthlyFromEnd(_, _)
+                    | Reminder::Workday(_)
+                    | Reminder::Easter
+                    | Reminder::Hebrew(_, _)
+                    | Reminder::Islamic(_, _)
+                    | Reminder::Julian(_, _)
+                    | Reminder::Astro(_)
+                    | Reminder::DayLength(_, _)
+            ),
+        }
+    }
+
+    /// Date from which occurrences of the reminder are counted, if any.
+    /// For anniversaries, this is the origin which is not an occurrence.
+    pub fn anchor(&self) -> Option<time::Date> {
+        match self {
+            Reminder::Anniversary(day, mon, year) => {
+                time::Date::from_calendar_date(*year, *mon, *day).ok()
+            }
+            Reminder::Date(d) => Some(*d),
+            Reminder::Shifted(inner, _) => inner.anchor(),
+            Reminder::Offset(inner, off) => inner.anchor()?.checked_add(*off),
+            Reminder::InYears(inner, YearFilter::Every(_, from)) => inner
+                .anchor()
+                .or_else(|| time::Date::from_calendar_date(*from, time::Month::January, 1).ok()),
+            Reminder::InYears(inner, _)
+            | Reminder::InWeeks(inner, _)
+            | Reminder::LeapDay(inner, _) => inner.anchor(),
+            _ => None,
+        }
+    }
+
+    /// Number of the occurrence on the given date, counting from the anchor
+    /// (which is the first occurrence). Returns None if there is no anchor.
+    pub fn count(&self, date: time::Date, days: &Workdays) -> Option<usize> {
+        if let Reminder::Shifted(inner, shift) = self {
+            return inner.count(Self::unshifted(inner, *shift, date, days)?, days);
+        }
+
+        let mut day = self.anchor()?;
+
+        let mut n = 0;
+        while day <= date {
+            if self.matches_on(day, days) {
+                n += 1;
+            }
+            day = day.next_day()?;
+        }
+
+        Some(n)
+    }
+
+    /// Amount of years since the origin of an anniversary.
+    pub fn age(&self, date: time::Date, days: &Workdays) -> Option<i32> {
+        match self {
+            Reminder::Anniversary(_, _, year) => Some(date.year() - year),
+            Reminder::Shifted(inner, shift) => {
+                inner.age(Self::unshifted(inner, *shift, date, days)?, days)
+            }
+            Reminder::Offset(inner, off) => inner.age(date.checked_sub(*off)?, days),
+            Reminder::InYears(inner, _)
+            | Reminder::InWeeks(inner, _)
+            | Reminder::LeapDay(inner, _) => inner.age(date, days),
+            _ => None,
+        }
+    }
+
+    /// Find the first matching date within the given amount of days
+    /// after (and including) the given date.
+    pub fn next_occurrence(
+        &self,
+        date: time::Date,
+        limit: u32,
+        days: &Workdays,
+    ) -> Option<time::Date> {
+        let mut day = date;
+        for _ in 0..limit {
+            if self.matches_on(day, days) {
+                return Some(day);
+            }
+            day = day.next_day()?;
+        }
+
+        Some(day).filter(|d| self.matches_on(*d, days))
+    }
 }
 
 /// Represents a single appointment from the calendar file.
@@ -123,30 +481,87 @@ impl Reminder {
 pub struct Entry {
     pub day: Reminder,
     pub desc: String,
+    /// Days of advance warning before each occurrence.
+    pub lead: u32,
+    /// Whether the entry is a non-working day.
+    pub holiday: bool,
+    /// Tags, location, priority and URL of the entry.
+    pub meta: meta::Meta,
     //pub time: time::Time,
 }
 
 impl Entry {
+    /// Returns the next occurrence of the entry which is either on the
+    /// given date or on one of the following days within its lead time.
+    pub fn upcoming(&self, date: time::Date, days: &Workdays) -> Option<time::Date> {
+        self.day.next_occurrence(date, self.lead, days)
+    }
+
+    /// Returns the description of the entry with all placeholders expanded
+    /// for the occurrence on the given date, as printed on the from date.
+    /// Names of weekdays are written according to the given locale.
+    pub fn description(
+        &self,
+        from: time::Date,
+        date: time::Date,
+        days: &Workdays,
+        locale: Locale,
+    ) -> String {
+        let iso_fmt = format_description!("[year]-[month]-[day]");
+
+        subst::expand(&self.desc, |name| match name {
+            "age" => self.day.age(date, days).map(|n| n.to_string()),
+            "nth" => self.day.age(date, days).map(subst::ordinal),
+            "date" => date.format(&iso_fmt).ok(),
+            "weekday" => Some(locale.weekday(date.weekday()).to_string()),
+            "days_until" => Some((date - from).whole_days().to_string()),
+            "week" => Some(date.iso_week().to_string()),
+            "doy" => Some(date.ordinal().to_string()),
+            "count" => self.day.count(date, days).map(|n| n.to_string()),
+            _ => None,
+        })
+    }
+
     pub fn is_fixed(&self) -> bool {
-        match self.day {
-            Reminder::SemiWeekly(_, _) | Reminder::Weekly(_) | Reminder::Monthly(_, _) => false,
-            _ => true,
-        }
+        self.day.is_fixed()
     }
 }
 
 ////////////////////////////////////////////////////////////////////////
 
-pub fn parse_file<'a, P: convert::AsRef<path::Path>>(fp: P) -> Result<Vec<Entry>, Error> {
+/// Parse the given calendar file, names of weekdays and months
+/// are parsed according to the given locale.
+pub fn parse_file<P: convert::AsRef<path::Path>>(
+    fp: P,
+    locale: Locale,
+) -> Result<Vec<Entry>, Error> {
     let out = cpp::preprocess(fp)?;
-    let (input, entries) = parse_entries(&out)?;
-    if input != "" {
-        Err(Error::IncompleteParse)
+    let (input, entries) = parse_entries(locale)(&out)?;
+    if !input.is_empty() {
+        let line = input.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+        if let Some((name, suggestion)) = suggest(line, locale) {
+            return Err(Error::UnknownName(line.to_string(), name, suggestion));
+        }
+        if let Some(lead) = ambiguous_lead(line, locale) {
+            return Err(Error::AmbiguousLead(line.to_string(), lead));
+        }
+
+        // The remaining input starts with the first entry which
+        // could not be parsed, report it analog to all_consuming.
+        Err(Error::ParsingError(
+            input.to_string(),
+            nom::error::ErrorKind::Eof,
+        ))
     } else {
         Ok(entries)
     }
 }
 
+pub fn parse_config<P: convert::AsRef<path::Path>>(fp: P) -> Result<Config, Error> {
+    let input = fs::read_to_string(fp)?;
+    config::parse_config(&input)
+}
+
 ////////////////////////////////////////////////////////////////////////
 
 #[cfg(test)]
@@ -178,4 +593,375 @@ mod tests {
         assert!(rem4.matches(date!(2023 - 05 - 30)));
         assert!(rem4.matches(date!(2023 - 08 - 29)));
     }
+
+    #[test]
+    fn match_semiweekly_in() {
+        // Mother's day: Second Sunday in May
+        let rem0 = Reminder::SemiWeeklyIn(
+            time::Weekday::Sunday,
+            2.try_into().unwrap(),
+            time::Month::May,
+        );
+        assert!(rem0.matches(date!(2023 - 05 - 14)));
+        assert!(rem0.matches(date!(2024 - 05 - 12)));
+        assert!(!rem0.matches(date!(2023 - 06 - 11)));
+        assert!(!rem0.matches(date!(2023 - 05 - 07)));
+
+        // Memorial day: Last Monday in May
+        let rem1 = Reminder::SemiWeeklyIn(
+            time::Weekday::Monday,
+            (-1).try_into().unwrap(),
+            time::Month::May,
+        );
+        assert!(rem1.matches(date!(2023 - 05 - 29)));
+        assert!(rem1.matches(date!(2024 - 05 - 27)));
+        assert!(!rem1.matches(date!(2023 - 07 - 31)));
+    }
+
+    #[test]
+    fn match_lists() {
+        let rem0 = Reminder::WeeklyList(vec![time::Weekday::Monday, time::Weekday::Friday]);
+        assert!(rem0.matches(date!(2023 - 05 - 01)));
+        assert!(rem0.matches(date!(2023 - 05 - 05)));
+        assert!(!rem0.matches(date!(2023 - 05 - 03)));
+
+        let rem1 = Reminder::YearlyList(15, vec![time::Month::January, time::Month::April]);
+        assert!(rem1.matches(date!(2023 - 01 - 15)));
+        assert!(rem1.matches(date!(2023 - 04 - 15)));
+        assert!(!rem1.matches(date!(2023 - 02 - 15)));
+        assert!(!rem1.matches(date!(2023 - 04 - 16)));
+    }
+
+    #[test]
+    fn match_monthly_end() {
+        let last = Reminder::MonthlyFromEnd(0, None);
+        assert!(last.matches(date!(2023 - 02 - 28)));
+        assert!(last.matches(date!(2024 - 02 - 29)));
+        assert!(last.matches(date!(2023 - 04 - 30)));
+        assert!(!last.matches(date!(2023 - 05 - 30)));
+
+        let third = Reminder::MonthlyFromEnd(2, Some(2023));
+        assert!(third.matches(date!(2023 - 02 - 26)));
+        assert!(third.matches(date!(2023 - 12 - 29)));
+        assert!(!third.matches(date!(2024 - 12 - 29)));
+
+        let clamped = Reminder::MonthlyClamped(31, None);
+        assert!(clamped.matches(date!(2023 - 04 - 30)));
+        assert!(clamped.matches(date!(2023 - 02 - 28)));
+        assert!(clamped.matches(date!(2023 - 05 - 31)));
+        assert!(!clamped.matches(date!(2023 - 05 - 30)));
+    }
+
+    #[test]
+    fn match_workday() {
+        let holidays = vec![Entry {
+            day: Reminder::Yearly(1, time::Month::May),
+            desc: "Labour Day".to_string(),
+            lead: 0,
+            holiday: true,
+            meta: Default::default(),
+        }];
+        let days = Workdays::new(&[time::Weekday::Saturday, time::Weekday::Sunday], &holidays);
+
+        let third = Reminder::Workday(3);
+        assert!(third.matches_on(date!(2023 - 05 - 04), &days));
+        assert!(!third.matches_on(date!(2023 - 05 - 03), &days));
+        assert!(third.matches_on(date!(2023 - 04 - 05), &days));
+
+        let last = Reminder::Workday(-1);
+        assert!(last.matches_on(date!(2023 - 04 - 28), &days));
+        assert!(last.matches_on(date!(2023 - 05 - 31), &days));
+        assert!(!last.matches_on(date!(2023 - 04 - 30), &days));
+
+        let fri_sat = Workdays::new(&[time::Weekday::Friday, time::Weekday::Saturday], &[]);
+        assert!(last.matches_on(date!(2023 - 03 - 30), &fri_sat));
+        assert!(!last.matches_on(date!(2023 - 03 - 31), &fri_sat));
+    }
+
+    #[test]
+    fn match_shifted() {
+        let holidays = vec![Entry {
+            day: Reminder::Yearly(1, time::Month::May),
+            desc: "Labour Day".to_string(),
+            lead: 0,
+            holiday: true,
+            meta: Default::default(),
+        }];
+        let days = Workdays::new(&[time::Weekday::Saturday, time::Weekday::Sunday], &holidays);
+
+        // Saturday, 29th April 2023 → Tuesday, 2nd May 2023
+        let next = Reminder::Shifted(
+            Box::new(Reminder::Yearly(29, time::Month::April)),
+            Shift::Forward,
+        );
+        assert!(next.matches_on(date!(2023 - 05 - 02), &days));
+        assert!(!next.matches_on(date!(2023 - 04 - 29), &days));
+        assert!(!next.matches_on(date!(2023 - 05 - 01), &days));
+        assert!(next.matches_on(date!(2024 - 04 - 29), &days));
+
+        let prev = Reminder::Shifted(Box::new(Reminder::Monthly(1, None)), Shift::Backward);
+        assert!(prev.matches_on(date!(2023 - 04 - 28), &days));
+        assert!(!prev.matches_on(date!(2023 - 05 - 01), &days));
+        assert!(prev.matches_on(date!(2023 - 06 - 01), &days));
+
+        // Saturday, 31st December 2022 → Monday, 2nd January 2023
+        let birthday = Reminder::Shifted(
+            Box::new(Reminder::Anniversary(31, time::Month::December, 1990)),
+            Shift::Forward,
+        );
+        assert!(birthday.matches_on(date!(2023 - 01 - 02), &days));
+        assert_eq!(birthday.age(date!(2023 - 01 - 02), &days), Some(32));
+        assert_eq!(birthday.count(date!(2023 - 01 - 02), &days), Some(32));
+        assert_eq!(birthday.age(date!(2022 - 01 - 02), &days), None);
+    }
+
+    #[test]
+    fn match_offset() {
+        // Black Friday: Day after the fourth Thursday in November
+        let rem0 = Reminder::Offset(
+            Box::new(Reminder::SemiWeeklyIn(
+                time::Weekday::Thursday,
+                4.try_into().unwrap(),
+                time::Month::November,
+            )),
+            time::Duration::days(1),
+        );
+        assert!(rem0.matches(date!(2023 - 11 - 24)));
+        assert!(rem0.matches(date!(2024 - 11 - 29)));
+        assert!(!rem0.matches(date!(2023 - 11 - 23)));
+
+        let rem1 = Reminder::Offset(
+            Box::new(Reminder::Yearly(1, time::Month::January)),
+            time::Duration::days(-3),
+        );
+        assert!(rem1.matches(date!(2023 - 12 - 29)));
+        assert!(!rem1.matches(date!(2023 - 01 - 01)));
+    }
+
+    #[test]
+    fn match_years() {
+        let rem0 = Reminder::InYears(
+            Box::new(Reminder::Yearly(1, time::Month::June)),
+            YearFilter::Every(2, 2022),
+        );
+        assert!(rem0.matches(date!(2022 - 06 - 01)));
+        assert!(rem0.matches(date!(2024 - 06 - 01)));
+        assert!(!rem0.matches(date!(2023 - 06 - 01)));
+        assert!(!rem0.matches(date!(2020 - 06 - 01)));
+        assert_eq!(
+            rem0.count(date!(2026 - 06 - 01), &Workdays::default()),
+            Some(3)
+        );
+
+        let rem1 = Reminder::InYears(
+            Box::new(Reminder::Weekly(time::Weekday::Monday)),
+            YearFilter::Odd,
+        );
+        assert!(rem1.matches(date!(2023 - 05 - 01)));
+        assert!(!rem1.matches(date!(2024 - 05 - 06)));
+
+        let rem2 = Reminder::InYears(Box::new(Reminder::Monthly(29, None)), YearFilter::Leap);
+        assert!(rem2.matches(date!(2024 - 05 - 29)));
+        assert!(!rem2.matches(date!(2023 - 05 - 29)));
+        assert!(!rem2.matches(date!(1900 - 05 - 29)));
+    }
+
+    #[test]
+    fn match_weeks() {
+        let rem0 = Reminder::InWeeks(
+            Box::new(Reminder::Weekly(time::Weekday::Tuesday)),
+            WeekFilter::Even,
+        );
+        assert!(rem0.matches(date!(2024 - 01 - 09)));
+        assert!(!rem0.matches(date!(2024 - 01 - 02)));
+        assert!(!rem0.matches(date!(2024 - 01 - 10)));
+
+        let rem1 = Reminder::InWeeks(
+            Box::new(Reminder::Weekly(time::Weekday::Monday)),
+            WeekFilter::Range(14, 18),
+        );
+        assert!(rem1.matches(date!(2024 - 04 - 01)));
+        assert!(rem1.matches(date!(2024 - 04 - 29)));
+        assert!(!rem1.matches(date!(2024 - 05 - 06)));
+        assert!(!rem1.matches(date!(2024 - 03 - 25)));
+
+        let rem2 = Reminder::InWeeks(
+            Box::new(Reminder::Weekly(time::Weekday::Monday)),
+            WeekFilter::Range(52, 1),
+        );
+        assert!(rem2.matches(date!(2024 - 12 - 30)));
+        assert!(rem2.matches(date!(2024 - 12 - 23)));
+        assert!(!rem2.matches(date!(2025 - 01 - 06)));
+    }
+
+    #[test]
+    fn match_leapday() {
+        let rem0 = Reminder::LeapDay(
+            Box::new(Reminder::Yearly(29, time::Month::February)),
+            Feb29::Feb28,
+        );
+        assert!(rem0.matches(date!(2024 - 02 - 29)));
+        assert!(!rem0.matches(date!(2024 - 02 - 28)));
+        assert!(rem0.matches(date!(2023 - 02 - 28)));
+        assert!(!rem0.skips_feb29());
+        assert!(Reminder::Yearly(29, time::Month::February).skips_feb29());
+        assert!(!Reminder::Yearly(28, time::Month::February).skips_feb29());
+
+        let rem1 = Reminder::LeapDay(
+            Box::new(Reminder::Anniversary(29, time::Month::February, 1996)),
+            Feb29::Mar1,
+        );
+        assert!(rem1.matches(date!(2023 - 03 - 01)));
+        assert!(!rem1.matches(date!(2024 - 03 - 01)));
+        assert!(!rem1.matches(date!(1995 - 03 - 01)));
+        assert_eq!(
+            rem1.age(date!(2023 - 03 - 01), &Workdays::default()),
+            Some(27)
+        );
+
+        let days = Workdays::default();
+        assert_eq!(
+            rem1.next_occurrence(date!(2023 - 02 - 20), 14, &days),
+            Some(date!(2023 - 03 - 01))
+        );
+
+        let mut rem2 = Reminder::InYears(
+            Box::new(Reminder::Yearly(29, time::Month::February)),
+            YearFilter::Odd,
+        );
+        rem2.set_feb29(Feb29::Feb28);
+        assert!(rem2.matches(date!(2023 - 02 - 28)));
+        assert!(!rem2.matches(date!(2022 - 02 - 28)));
+
+        // An explicit policy is not overwritten.
+        let mut rem3 = rem0.clone();
+        rem3.set_feb29(Feb29::Mar1);
+        assert_eq!(rem3, rem0);
+    }
+
+    #[test]
+    fn match_daylength() {
+        let berlin = sun::Location {
+            latitude: 52.52,
+            longitude: 13.405,
+        };
+
+        // The day length in Berlin exceeds 12 hours shortly before the
+        // March equinox and falls below it shortly after the September one.
+        let rem0 = Reminder::DayLength(time::Duration::hours(12), Some(berlin));
+        assert!(rem0.matches(date!(2024 - 03 - 18)));
+        assert!(!rem0.matches(date!(2024 - 03 - 19)));
+        assert!(rem0.matches(date!(2024 - 09 - 26)));
+        assert!(!rem0.matches(date!(2024 - 06 - 20)));
+
+        let rem1 = Reminder::DayLength(time::Duration::hours(12), None);
+        assert!(!rem1.matches(date!(2024 - 03 - 17)));
+    }
+
+    #[test]
+    fn match_calendars() {
+        let passover = Reminder::Hebrew(15, calendars::HebrewMonth::Nisan);
+        assert!(passover.matches(date!(2024 - 04 - 23)));
+        assert!(passover.matches(date!(2025 - 04 - 13)));
+        assert!(!passover.matches(date!(2025 - 04 - 23)));
+
+        let christmas = Reminder::Julian(25, time::Month::December);
+        assert!(christmas.matches(date!(2025 - 01 - 07)));
+        assert!(!christmas.matches(date!(2024 - 12 - 25)));
+
+        let ramadan = Reminder::Islamic(1, 9);
+        let days = Workdays::default();
+        assert_eq!(
+            ramadan.next_occurrence(date!(2025 - 01 - 01), 366, &days),
+            Some(date!(2025 - 03 - 01))
+        );
+    }
+
+    #[test]
+    fn upcoming() {
+        let entry = Entry {
+            day: Reminder::Yearly(25, time::Month::February),
+            desc: "Birthday".to_string(),
+            lead: 7,
+            holiday: false,
+            meta: Default::default(),
+        };
+
+        let days = Workdays::default();
+        assert_eq!(
+            entry.upcoming(date!(2023 - 02 - 18), &days),
+            Some(date!(2023 - 02 - 25))
+        );
+        assert_eq!(
+            entry.upcoming(date!(2023 - 02 - 25), &days),
+            Some(date!(2023 - 02 - 25))
+        );
+        assert_eq!(entry.upcoming(date!(2023 - 02 - 17), &days), None);
+        assert_eq!(entry.upcoming(date!(2023 - 02 - 26), &days), None);
+    }
+
+    #[test]
+    fn anniversary() {
+        let entry = Entry {
+            day: Reminder::Anniversary(25, time::Month::February, 1990),
+            desc: "Anna's {nth} birthday ({age} years)".to_string(),
+            lead: 0,
+            holiday: false,
+            meta: Default::default(),
+        };
+
+        assert!(!entry.day.matches(date!(1990 - 02 - 25)));
+        assert!(entry.day.matches(date!(1991 - 02 - 25)));
+        assert!(entry.day.matches(date!(2024 - 02 - 25)));
+        assert!(!entry.day.matches(date!(1989 - 02 - 25)));
+        let date = date!(2024 - 02 - 25);
+        assert_eq!(
+            entry.description(date, date, &Workdays::default(), Locale::default()),
+            "Anna's 34th birthday (34 years)"
+        );
+    }
+
+    #[test]
+    fn placeholders() {
+        let entry = Entry {
+            day: Reminder::Anniversary(20, time::Month::December, 2020),
+            desc: "{date} {weekday} {days_until} {week} {doy} {count} {foo}".to_string(),
+            lead: 0,
+            holiday: false,
+            meta: Default::default(),
+        };
+
+        assert_eq!(
+            entry.description(
+                date!(2024 - 12 - 17),
+                date!(2024 - 12 - 20),
+                &Workdays::default(),
+                Locale::default()
+            ),
+            "2024-12-20 Friday 3 51 355 4 {foo}"
+        );
+
+        let de = Locale {
+            language: locale::Language::German,
+            ignore_case: true,
+        };
+        let date = date!(2024 - 12 - 20);
+        assert_eq!(
+            entry.description(date, date, &Workdays::default(), de),
+            "2024-12-20 Freitag 0 51 355 4 {foo}"
+        );
+
+        let entry = Entry {
+            day: Reminder::Weekly(time::Weekday::Monday),
+            desc: "{count} \\{weekday} {}".to_string(),
+            lead: 0,
+            holiday: false,
+            meta: Default::default(),
+        };
+        let date = date!(2024 - 12 - 16);
+        assert_eq!(
+            entry.description(date, date, &Workdays::default(), Locale::default()),
+            "{count} {weekday} {}"
+        );
+    }
 }
@@ -0,0 +1,90 @@
+// Expand all `{name}` placeholders in the given string for which the
+// lookup function returns a value. Unknown placeholders and all other
+// braces are retained as is. Expansion of known placeholders can be
+// prevented by escaping them with a backslash, e.g. `\{name}`.
+pub fn expand<F: Fn(&str) -> Option<String>>(input: &str, lookup: F) -> String {
+    let mut out = String::with_capacity(input.len());
+
+    let mut rest = input;
+    while let Some(start) = rest.find('{') {
+        let after = &rest[start + 1..];
+        let value = after
+            .find('}')
+            .and_then(|end| lookup(&after[..end]).map(|val| (end, val)));
+
+        match value {
+            Some((end, _)) if rest[..start].ends_with('\\') => {
+                out.push_str(&rest[..start - 1]);
+                out.push_str(&rest[start..start + end + 2]);
+                rest = &after[end + 1..];
+            }
+            Some((end, val)) => {
+                out.push_str(&rest[..start]);
+                out.push_str(&val);
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push_str(&rest[..=start]);
+                rest = after;
+            }
+        }
+    }
+
+    out.push_str(rest);
+    out
+}
+
+// Format the given number as an English ordinal (1st, 2nd, …).
+pub fn ordinal(n: i32) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+
+    format!("{}{}", n, suffix)
+}
+
+////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expansion() {
+        let lookup = |name: &str| -> Option<String> {
+            match name {
+                "age" => Some("34".to_string()),
+                _ => None,
+            }
+        };
+
+        assert_eq!(expand("turns {age}", lookup), "turns 34");
+        assert_eq!(expand("{age}{age}", lookup), "3434");
+        assert_eq!(expand("{foo} {age", lookup), "{foo} {age");
+        assert_eq!(expand("{}{{age}}", lookup), "{}{34}");
+        assert_eq!(expand("no braces", lookup), "no braces");
+
+        assert_eq!(expand("\\{age} is {age}", lookup), "{age} is 34");
+        assert_eq!(expand("\\{foo} \\{", lookup), "\\{foo} \\{");
+        assert_eq!(expand("{{age}", lookup), "{34");
+    }
+
+    #[test]
+    fn ordinals() {
+        assert_eq!(ordinal(1), "1st");
+        assert_eq!(ordinal(2), "2nd");
+        assert_eq!(ordinal(3), "3rd");
+        assert_eq!(ordinal(4), "4th");
+        assert_eq!(ordinal(11), "11th");
+        assert_eq!(ordinal(12), "12th");
+        assert_eq!(ordinal(13), "13th");
+        assert_eq!(ordinal(21), "21st");
+        assert_eq!(ordinal(34), "34th");
+        assert_eq!(ordinal(111), "111th");
+        assert_eq!(ordinal(122), "122nd");
+    }
+}
@@ -0,0 +1,109 @@
+//! Computation of sunrise and sunset times using the sunrise equation.
+//! Times are in UTC and accurate to about a minute for moderate latitudes.
+
+use crate::astro::{cos, from_jd, sin};
+
+/// Geographic position of the observer in degrees (north and east are positive).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Location {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+// Julian day of 1st January 2000, 12:00 UTC.
+const J2000: f64 = 2451545.0;
+
+fn asin(x: f64) -> f64 {
+    x.asin().to_degrees()
+}
+
+fn acos(x: f64) -> f64 {
+    x.acos().to_degrees()
+}
+
+// Returns the Julian day of the solar transit (noon) and the cosine of the
+// hour angle of sunrise and sunset. The cosine is greater than 1 if the sun
+// does not rise and less than -1 if the sun does not set on the given date.
+fn sunrise_equation(date: time::Date, loc: &Location) -> (f64, f64) {
+    let n = f64::from(date.to_julian_day()) - J2000 + 0.0008;
+    let mean = n - loc.longitude / 360.0;
+
+    let m = (357.5291 + 0.98560028 * mean).rem_euclid(360.0);
+    let c = 1.9148 * sin(m) + 0.02 * sin(2.0 * m) + 0.0003 * sin(3.0 * m);
+    let lambda = (m + c + 180.0 + 102.9372).rem_euclid(360.0);
+    let transit = J2000 + mean + 0.0053 * sin(m) - 0.0069 * sin(2.0 * lambda);
+
+    let decl = asin(sin(lambda) * sin(23.4397));
+    let cos_w = (sin(-0.833) - sin(loc.latitude) * sin(decl)) / (cos(loc.latitude) * cos(decl));
+
+    (transit, cos_w)
+}
+
+/// Returns the times of sunrise and sunset on the given date, or None if
+/// the sun does not rise (polar night) or does not set (midnight sun).
+pub fn sunrise_sunset(
+    date: time::Date,
+    loc: &Location,
+) -> Option<(time::OffsetDateTime, time::OffsetDateTime)> {
+    let (transit, cos_w) = sunrise_equation(date, loc);
+    if !(-1.0..=1.0).contains(&cos_w) {
+        return None;
+    }
+
+    let w = acos(cos_w);
+    Some((from_jd(transit - w / 360.0)?, from_jd(transit + w / 360.0)?))
+}
+
+/// Returns the time between sunrise and sunset on the given date.
+pub fn day_length(date: time::Date, loc: &Location) -> time::Duration {
+    let (_, cos_w) = sunrise_equation(date, loc);
+    let w = acos(cos_w.clamp(-1.0, 1.0));
+    time::Duration::seconds((w / 180.0 * 86400.0).round() as i64)
+}
+
+////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::{date, datetime};
+
+    const BERLIN: Location = Location {
+        latitude: 52.52,
+        longitude: 13.405,
+    };
+
+    fn assert_near(t: time::OffsetDateTime, expected: time::OffsetDateTime) {
+        assert!((t - expected).abs() < time::Duration::minutes(3), "{}", t);
+    }
+
+    #[test]
+    fn berlin() {
+        let (rise, set) = sunrise_sunset(date!(2024 - 06 - 20), &BERLIN).unwrap();
+        assert_near(rise, datetime!(2024-06-20 02:43 UTC));
+        assert_near(set, datetime!(2024-06-20 19:33 UTC));
+
+        let (rise, set) = sunrise_sunset(date!(2024 - 12 - 21), &BERLIN).unwrap();
+        assert_near(rise, datetime!(2024-12-21 07:15 UTC));
+        assert_near(set, datetime!(2024-12-21 14:54 UTC));
+    }
+
+    #[test]
+    fn polar() {
+        let tromso = Location {
+            latitude: 69.65,
+            longitude: 18.96,
+        };
+
+        assert_eq!(sunrise_sunset(date!(2024 - 12 - 21), &tromso), None);
+        assert_eq!(
+            day_length(date!(2024 - 12 - 21), &tromso),
+            time::Duration::ZERO
+        );
+        assert_eq!(sunrise_sunset(date!(2024 - 06 - 21), &tromso), None);
+        assert_eq!(
+            day_length(date!(2024 - 06 - 21), &tromso),
+            time::Duration::DAY
+        );
+    }
+}
@@ -2,25 +2,25 @@ use nom::{
     branch::alt,
     bytes::complete::tag,
     character::complete::{char, line_ending, one_of},
-    combinator::{map_res, recognize},
+    combinator::{map, map_res, opt, recognize},
     error::{FromExternalError, ParseError},
-    multi::{many0, many1},
-    sequence::delimited,
+    multi::{many0, many1, separated_list1},
+    sequence::{delimited, pair, preceded},
     IResult,
 };
 
 // Bind the given parser to the given value (map_res short).
-pub fn bind<'a, F: 'a, T: Copy, O, E: ParseError<&'a str> + FromExternalError<&'a str, ()>>(
+pub fn bind<'a, F, T: Copy, O, E: ParseError<&'a str> + FromExternalError<&'a str, ()>>(
     inner: F,
     val: T,
 ) -> impl FnMut(&'a str) -> IResult<&'a str, T, E>
 where
-    F: FnMut(&'a str) -> IResult<&'a str, O, E>,
+    F: FnMut(&'a str) -> IResult<&'a str, O, E> + 'a,
 {
     map_res(inner, move |_| -> Result<T, ()> { Ok(val) })
 }
 
-// Parse on of the given strings and return the given value.
+// Parse one of the given strings and return the given value.
 pub fn str<'a, T: Copy, E: ParseError<&'a str> + FromExternalError<&'a str, ()> + 'a>(
     name: &'a str,
     other: &'a str,
@@ -31,24 +31,55 @@ pub fn str<'a, T: Copy, E: ParseError<&'a str> + FromExternalError<&'a str, ()>
 
 pub fn digits(input: &str) -> IResult<&str, u32> {
     map_res(recognize(many1(one_of("0123456789"))), |input: &str| {
-        u32::from_str_radix(input, 10)
+        input.parse::<u32>()
     })(input)
 }
 
-pub fn ws<'a, F: 'a, O, E: ParseError<&'a str>>(
+// Parse a comma-separated list of items or inclusive ranges of items
+// (e.g. Mon..Fri), ranges are expanded using the given successor function.
+pub fn list<'a, F, T, E: ParseError<&'a str> + 'a>(
+    item: F,
+    succ: fn(T) -> T,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<T>, E>
+where
+    F: Fn(&'a str) -> IResult<&'a str, T, E> + Copy + 'a,
+    T: Copy + PartialEq,
+{
+    map(
+        separated_list1(ws(char(',')), pair(item, opt(preceded(tag(".."), item)))),
+        move |ranges| {
+            let mut items = Vec::new();
+            for (start, end) in ranges {
+                let mut cur = start;
+                items.push(cur);
+
+                if let Some(end) = end {
+                    while cur != end {
+                        cur = succ(cur);
+                        items.push(cur);
+                    }
+                }
+            }
+
+            items
+        },
+    )
+}
+
+pub fn ws<'a, F, O, E: ParseError<&'a str>>(
     inner: F,
 ) -> impl FnMut(&'a str) -> IResult<&'a str, O, E>
 where
-    F: Fn(&'a str) -> IResult<&'a str, O, E>,
+    F: Fn(&'a str) -> IResult<&'a str, O, E> + 'a,
 {
     delimited(many0(char(' ')), inner, many0(char(' ')))
 }
 
-pub fn empty_lines<'a, F: 'a, O, E: ParseError<&'a str> + 'a>(
+pub fn empty_lines<'a, F, O, E: ParseError<&'a str> + 'a>(
     inner: F,
 ) -> impl FnMut(&'a str) -> IResult<&'a str, O, E>
 where
-    F: Fn(&'a str) -> IResult<&'a str, O, E>,
+    F: Fn(&'a str) -> IResult<&'a str, O, E> + 'a,
 {
     delimited(many0(ws(line_ending)), inner, many0(ws(line_ending)))
 }
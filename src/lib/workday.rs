@@ -0,0 +1,116 @@
+use crate::*;
+
+/// Distinguishes working days from weekends and holidays.
+#[derive(Debug)]
+pub struct Workdays {
+    weekend: Vec<time::Weekday>,
+    holidays: Vec<Reminder>,
+}
+
+impl Default for Workdays {
+    fn default() -> Self {
+        Workdays {
+            weekend: vec![time::Weekday::Saturday, time::Weekday::Sunday],
+            holidays: Vec::new(),
+        }
+    }
+}
+
+impl Workdays {
+    /// Create a new instance from the given weekend days, all
+    /// entries marked as holidays are considered non-working days.
+    pub fn new(weekend: &[time::Weekday], entries: &[Entry]) -> Self {
+        Workdays {
+            weekend: weekend.to_vec(),
+            holidays: entries
+                .iter()
+                .filter(|e| e.holiday)
+                .map(|e| e.day.clone())
+                .collect(),
+        }
+    }
+
+    pub fn is_holiday(&self, date: time::Date) -> bool {
+        // Holidays themselves are evaluated without considering holidays.
+        let weekend = Workdays {
+            weekend: self.weekend.clone(),
+            holidays: Vec::new(),
+        };
+
+        self.holidays.iter().any(|h| h.matches_on(date, &weekend))
+    }
+
+    pub fn is_workday(&self, date: time::Date) -> bool {
+        !self.weekend.contains(&date.weekday()) && !self.is_holiday(date)
+    }
+
+    /// Returns all working days of the given month.
+    pub fn filter(&self, year: Year, month: time::Month) -> Vec<time::Date> {
+        let mut days = Vec::new();
+
+        let mut day = time::Date::from_calendar_date(year, month, 1).ok();
+        while let Some(d) = day.filter(|d| d.month() == month) {
+            if self.is_workday(d) {
+                days.push(d);
+            }
+            day = d.next_day();
+        }
+
+        days
+    }
+
+    /// Find the first working day after the given date. Returns
+    /// None if there is no working day within the following year.
+    pub fn next_workday(&self, date: time::Date) -> Option<time::Date> {
+        let mut day = date;
+        for _ in 0..366 {
+            day = day.next_day()?;
+            if self.is_workday(day) {
+                return Some(day);
+            }
+        }
+
+        None
+    }
+}
+
+////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::date;
+
+    #[test]
+    fn next_workday() {
+        let entries = vec![Entry {
+            day: Reminder::Yearly(1, time::Month::May),
+            desc: "Labour Day".to_string(),
+            lead: 0,
+            holiday: true,
+            meta: Default::default(),
+        }];
+
+        let sat_sun = [time::Weekday::Saturday, time::Weekday::Sunday];
+        let w = Workdays::new(&sat_sun, &entries);
+        assert_eq!(
+            w.next_workday(date!(2023 - 04 - 28)),
+            Some(date!(2023 - 05 - 02))
+        );
+        assert_eq!(
+            w.next_workday(date!(2023 - 05 - 02)),
+            Some(date!(2023 - 05 - 03))
+        );
+
+        let fri_sat = [time::Weekday::Friday, time::Weekday::Saturday];
+        let w = Workdays::new(&fri_sat, &[]);
+        assert_eq!(
+            w.next_workday(date!(2023 - 04 - 27)),
+            Some(date!(2023 - 04 - 30))
+        );
+
+        let w = Workdays::new(&fri_sat, &[]);
+        assert!(w.is_workday(date!(2023 - 04 - 30)));
+        assert!(!w.is_workday(date!(2023 - 04 - 29)));
+    }
+}
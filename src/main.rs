@@ -1,15 +1,23 @@
 extern crate ncalendar;
 extern crate structopt;
 
+mod error;
 mod timespan;
 mod util;
 
-use crate::timespan::TimeSpan;
+use crate::error::*;
+use crate::timespan::{Period, TimeSpan};
 use crate::util::*;
 
+use ncalendar::locale::Language;
+use ncalendar::meta::{Meta, Priority};
+use ncalendar::Reminder;
+use std::collections::HashSet;
+use std::io::{self, Write};
 use std::path;
+use std::process;
 use structopt::StructOpt;
-use time::format_description;
+use time::macros::format_description;
 
 #[derive(StructOpt, Debug)]
 #[structopt(name = "basic")]
@@ -19,57 +27,243 @@ struct Opt {
     #[structopt(short = "f", default_value = "", parse(try_from_str = parse_file))]
     file: path::PathBuf,
 
-    /// Amount of next days to consider.
-    #[structopt(short = "A", parse(try_from_str = parse_days))]
-    forward: Option<time::Duration>,
+    /// Amount of next days (or 2w, 1m, 1y, eom) to consider.
+    #[structopt(short = "A", allow_hyphen_values = true, parse(try_from_str = parse_days))]
+    forward: Option<Period>,
 
-    /// Amount of past days to consider.
-    #[structopt(short = "B", parse(try_from_str = parse_days))]
-    back: Option<time::Duration>,
+    /// Amount of past days (or 2w, 1m, 1y, eom) to consider.
+    #[structopt(short = "B", allow_hyphen_values = true, parse(try_from_str = parse_days))]
+    back: Option<Period>,
 
-    /// Act like the specified value is today.
-    #[structopt(short = "t", default_value = "today", parse(try_from_str = parse_today))]
+    /// Act like the specified value is today (e.g. DDMMYYYY, YYYY-MM-DD, DD.MM., +7, next-friday).
+    #[structopt(short = "t", allow_hyphen_values = true, default_value = "today", parse(try_from_str = parse_today))]
     today: time::Date,
 
     /// Print day of the week name in front of each event.
     #[structopt(short = "w")]
     week: bool,
+
+    /// Only print entries with the given tag (may be given multiple times).
+    #[structopt(long = "tag")]
+    tags: Vec<String>,
+
+    /// Only print entries with at least the given priority (low, medium or high).
+    #[structopt(long = "priority", parse(try_from_str = parse_priority))]
+    priority: Option<Priority>,
+
+    /// Print moon phases, equinoxes and solstices (computed for UTC).
+    #[structopt(long = "astro")]
+    astro: bool,
+
+    /// Print sunrise and sunset times for the location from the configuration file.
+    #[structopt(long = "sun")]
+    sun: bool,
 }
 
-// For Fridays (if neither -A nor -B was provided) look
-// three days into the future by default (next monday).
-fn forward_default(opt: &Opt) -> impl FnOnce() -> time::Duration {
-    let fri: bool = opt.today.weekday() == time::Weekday::Friday && opt.back.is_none();
-    move || -> time::Duration { time::Duration::days(if fri { 3 } else { 1 }) }
+// If neither -A nor -B was provided, look into the future until the
+// next working day by default (e.g. next monday for fridays).
+fn forward_default(opt: &Opt, workdays: &ncalendar::Workdays) -> time::Duration {
+    let next = workdays.next_workday(opt.today);
+    match next {
+        Some(day) if opt.back.is_none() => day - opt.today,
+        _ => time::Duration::days(1),
+    }
 }
 
-fn main() {
-    let opt = Opt::from_args();
-    let span = TimeSpan::new(
-        opt.today,
-        opt.back.unwrap_or(time::Duration::days(0)),
-        opt.forward.unwrap_or_else(forward_default(&opt)),
-    )
-    .unwrap();
+// Whether the given entry passes the tag and priority filters.
+fn selected(opt: &Opt, meta: &Meta) -> bool {
+    let tagged = opt.tags.is_empty() || opt.tags.iter().any(|t| meta.tags.contains(t));
+    let priority = match opt.priority {
+        Some(min) => meta.priority.is_some_and(|p| p >= min),
+        None => true,
+    };
+
+    tagged && priority
+}
+
+// Load the configuration file, using the default configuration if it doesn't exist.
+fn load_config() -> Result<ncalendar::config::Config, Error> {
+    let fp = match config_file() {
+        Ok(fp) if fp.exists() => fp,
+        _ => return Ok(ncalendar::config::Config::default()),
+    };
 
-    let out_fmt = format_description::parse("[month repr:short] [day]").unwrap();
-    let entries = ncalendar::parse_file(opt.file.as_path()).unwrap();
+    ncalendar::parse_config(&fp).map_err(|e| Error::Config(fp, e))
+}
+
+// Print all matching entries and return whether any entry matched.
+fn run(opt: &Opt) -> Result<bool, Error> {
+    let config = load_config()?;
+    // LANG only affects the output, as parsing the calendar file must not
+    // depend on the environment (which is often unset in cronjobs).
+    let locale = config.locale(env_language());
+    let mut entries = ncalendar::parse_file(opt.file.as_path(), config.locale(Language::English))
+        .map_err(|e| Error::Calendar(opt.file.clone(), e))?;
+    config.apply(&mut entries);
+    for entry in entries.iter().filter(|e| e.day.skips_feb29()) {
+        let desc = entry.desc.lines().next().unwrap_or("");
+        eprintln!(
+            "ncalendar: warning: '{}' is skipped in common years, as no feb29 policy is set",
+            desc
+        );
+    }
+    // Entries on a certain day length can't match without a location.
+    let daylength = |e: &ncalendar::Entry| matches!(e.day.base(), Reminder::DayLength(_, None));
+    if entries.iter().any(daylength) {
+        return Err(Error::NoLocation);
+    }
+    let workdays = ncalendar::Workdays::new(&config.weekend, &entries);
+
+    let back = match opt.back {
+        Some(p) => p.duration(opt.today, false).ok_or(Error::InvalidSpan)?,
+        None => time::Duration::days(0),
+    };
+    let forward = match opt.forward {
+        Some(p) => p.duration(opt.today, true).ok_or(Error::InvalidSpan)?,
+        None => forward_default(opt, &workdays),
+    };
 
+    let span = TimeSpan::new(opt.today, back, forward).ok_or(Error::InvalidSpan)?;
+    let location = match config.location() {
+        None if opt.sun => return Err(Error::NoLocation),
+        loc => loc,
+    };
+
+    let time_fmt = format_description!("[hour]:[minute]");
+
+    let mut out = io::stdout().lock();
+    let mut found = false;
+    let mut announced = HashSet::new();
     for date in span.iter() {
-        let matched = entries.iter().filter(|e| e.day.matches(date));
+        if opt.astro {
+            for (event, time) in ncalendar::astro::events(date) {
+                if opt.week {
+                    write!(out, "{} ", locale.weekday_short(date.weekday()))?;
+                }
+                writeln!(
+                    out,
+                    "{}*\t{} ({} UTC)",
+                    format_date(date, &locale),
+                    event,
+                    time.format(&time_fmt)?
+                )?;
+                found = true;
+            }
+        }
+
+        if let Some(loc) = location.filter(|_| opt.sun) {
+            if opt.week {
+                write!(out, "{} ", locale.weekday_short(date.weekday()))?;
+            }
+            write!(out, "{}*\t", format_date(date, &locale))?;
+            match ncalendar::sun::sunrise_sunset(date, &loc) {
+                Some((rise, set)) => writeln!(
+                    out,
+                    "Sunrise {}, sunset {}",
+                    local_time(rise).format(&time_fmt)?,
+                    local_time(set).format(&time_fmt)?
+                )?,
+                None if ncalendar::sun::day_length(date, &loc).is_zero() => {
+                    writeln!(out, "Polar night")?
+                }
+                None => writeln!(out, "Midnight sun")?,
+            };
+            found = true;
+        }
+
+        for (idx, entry) in entries.iter().enumerate() {
+            if !selected(opt, &entry.meta) {
+                continue;
+            }
+
+            // Upcoming occurrences which are not part of the time span
+            // are announced once on the first day within the lead time.
+            let next = match entry.upcoming(date, &workdays) {
+                Some(next) if next == date => next,
+                Some(next) if !span.contains(next) && announced.insert((idx, next)) => next,
+                _ => continue,
+            };
 
-        matched.for_each(|entry| {
             let postfix = if entry.is_fixed() { ' ' } else { '*' };
 
             if opt.week {
-                print!("{} ", weekday_short(date));
+                write!(out, "{} ", locale.weekday_short(date.weekday()))?;
             }
-            println!(
-                "{}{}\t{}",
-                date.format(&out_fmt).unwrap(),
-                postfix,
-                entry.desc
-            );
-        });
+            let desc = entry.description(date, next, &workdays, locale);
+            write!(out, "{}{}\t{}", format_date(date, &locale), postfix, desc)?;
+            if let Some(loc) = &entry.meta.location {
+                write!(out, " @{}", loc)?;
+            }
+            if let Some(url) = &entry.meta.url {
+                write!(out, " <{}>", url)?;
+            }
+            match (next - date).whole_days() {
+                0 => writeln!(out)?,
+                1 => writeln!(out, " (in 1 day)")?,
+                n => writeln!(out, " (in {} days)", n)?,
+            };
+            found = true;
+        }
+    }
+
+    out.flush()?;
+    Ok(found)
+}
+
+fn main() {
+    let opt = match Opt::from_args_safe() {
+        Ok(opt) => opt,
+        Err(e) if e.use_stderr() => {
+            eprintln!("{}", e.message);
+            process::exit(EXIT_USAGE);
+        }
+        Err(e) => {
+            // Help or version information was requested.
+            println!("{}", e.message);
+            process::exit(EXIT_MATCH);
+        }
+    };
+
+    let code = match run(&opt) {
+        Ok(true) => EXIT_MATCH,
+        Ok(false) => EXIT_NOMATCH,
+        Err(e) => {
+            eprintln!("ncalendar: {}", e);
+            e.exit_code()
+        }
+    };
+    process::exit(code);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use structopt::clap::ErrorKind;
+    use time::macros::date;
+
+    #[test]
+    fn hyphen_values() {
+        // Rejected by the date parser, not as an unknown argument.
+        let argv = ["ncalendar", "-f", "/dev/null", "-t", "-2024-12-20"];
+        let err = Opt::from_iter_safe(argv).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::ValueValidation);
+
+        // Negative periods are rejected by the period parser itself.
+        let argv = [
+            "ncalendar",
+            "-f",
+            "/dev/null",
+            "-t",
+            "2024-12-20",
+            "-B",
+            "-1",
+        ];
+        let err = Opt::from_iter_safe(argv).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::ValueValidation);
+
+        let opt = Opt::from_iter_safe(["ncalendar", "-f", "/dev/null", "-t", "2024-12-20", "-w"])
+            .unwrap();
+        assert_eq!(opt.today, date!(2024 - 12 - 20));
+        assert!(opt.week);
     }
 }
@@ -1,3 +1,50 @@
+/// Amount of time relative to a given day, as specified with -A and -B.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Period {
+    Days(u32),
+    Weeks(u32),
+    Months(u32),
+    Years(u32),
+    MonthBoundary, // End (forward) or start (backward) of month
+}
+
+// Add the given amount of months to a date, using the last day
+// of the resulting month if the day does not exist in that month.
+fn add_months(date: time::Date, months: i64) -> Option<time::Date> {
+    let idx = i64::from(date.year()) * 12 + i64::from(date.month() as u8 - 1) + months;
+    let year = i32::try_from(idx.div_euclid(12)).ok()?;
+    let month = time::Month::try_from(idx.rem_euclid(12) as u8 + 1).ok()?;
+
+    let day = date.day().min(month.length(year));
+    time::Date::from_calendar_date(year, month, day).ok()
+}
+
+impl Period {
+    /// Convert the period to a duration relative to the given day,
+    /// months and years are added using calendar arithmetic.
+    pub fn duration(&self, day: time::Date, forward: bool) -> Option<time::Duration> {
+        let sign: i64 = if forward { 1 } else { -1 };
+        let months = match *self {
+            Period::Days(n) => return Some(time::Duration::days(n.into())),
+            Period::Weeks(n) => return Some(time::Duration::weeks(n.into())),
+            Period::Months(n) => i64::from(n),
+            Period::Years(n) => i64::from(n) * 12,
+            Period::MonthBoundary => {
+                let last = day.month().length(day.year());
+                let days = if forward {
+                    last - day.day()
+                } else {
+                    day.day() - 1
+                };
+                return Some(time::Duration::days(days.into()));
+            }
+        };
+
+        let other = add_months(day, sign * months)?;
+        Some((other - day).abs())
+    }
+}
+
 /// Represents a time span between two dates.
 pub struct TimeSpan {
     start: time::Date,
@@ -21,9 +68,9 @@ impl<'a> Iterator for DayIterator<'a> {
                     return None;
                 }
                 self.off += 1;
-                return Some(ndate);
+                Some(ndate)
             }
-            None => return None,
+            None => None,
         }
     }
 }
@@ -36,9 +83,14 @@ impl TimeSpan {
         Some(TimeSpan { start, end })
     }
 
+    /// Whether the given date is part of the time span.
+    pub fn contains(&self, date: time::Date) -> bool {
+        self.start <= date && date <= self.end
+    }
+
     /// Iterate over all days in the given time span.
-    pub fn iter(&self) -> DayIterator {
-        return DayIterator { cur: self, off: 0 };
+    pub fn iter(&self) -> DayIterator<'_> {
+        DayIterator { cur: self, off: 0 }
     }
 }
 
@@ -59,4 +111,35 @@ mod tests {
         assert_eq!(it.next(), Some(date!(1980 - 03 - 21)));
         assert_eq!(it.next(), None);
     }
+
+    #[test]
+    fn period() {
+        let d = date!(2024 - 01 - 31);
+        assert_eq!(
+            Period::Weeks(2).duration(d, true),
+            Some(time::Duration::days(14))
+        );
+        assert_eq!(
+            Period::Months(1).duration(d, true),
+            Some(time::Duration::days(29))
+        );
+        assert_eq!(
+            Period::Months(1).duration(d, false),
+            Some(time::Duration::days(31))
+        );
+        assert_eq!(
+            Period::Years(1).duration(date!(2024 - 02 - 29), false),
+            Some(time::Duration::days(366))
+        );
+
+        let d = date!(2023 - 02 - 10);
+        assert_eq!(
+            Period::MonthBoundary.duration(d, true),
+            Some(time::Duration::days(18))
+        );
+        assert_eq!(
+            Period::MonthBoundary.duration(d, false),
+            Some(time::Duration::days(9))
+        );
+    }
 }
@@ -1,21 +1,43 @@
+use crate::error::ArgError;
+use crate::timespan::Period;
+
+use ncalendar::locale::{Language, Locale};
+use ncalendar::meta::Priority;
 use std::env;
-use std::num::ParseIntError;
 use std::path::{self, Path};
 use time::macros::format_description;
 
-pub fn weekday_short(date: time::Date) -> String {
-    let w = date.weekday();
+// Format the given date like "Dec 20", using the month names of the given locale.
+pub fn format_date(date: time::Date, locale: &Locale) -> String {
+    format!("{} {:02}", locale.month_short(date.month()), date.day())
+}
 
-    // Every weekday is at least three characters long.
-    // Hence, the .get() invocation should never panic.
-    w.to_string().get(0..3).unwrap().to_string()
+// Determine the language from the LANG environment variable, if any.
+pub fn env_language() -> Language {
+    env::var("LANG")
+        .ok()
+        .and_then(|lang| Language::from_code(&lang))
+        .unwrap_or(Language::English)
 }
 
-pub fn calendar_file() -> Result<path::PathBuf, env::VarError> {
+// Convert the given time to the local time zone, falling back to UTC.
+pub fn local_time(t: time::OffsetDateTime) -> time::OffsetDateTime {
+    t.to_offset(time::UtcOffset::local_offset_at(t).unwrap_or(time::UtcOffset::UTC))
+}
+
+fn ncalendar_dir() -> Result<path::PathBuf, env::VarError> {
     let home = env::var("HOME")?;
     let path = Path::new(&home);
 
-    Ok(path.join(".ncalendar").join("calendar"))
+    Ok(path.join(".ncalendar"))
+}
+
+pub fn calendar_file() -> Result<path::PathBuf, env::VarError> {
+    Ok(ncalendar_dir()?.join("calendar"))
+}
+
+pub fn config_file() -> Result<path::PathBuf, env::VarError> {
+    Ok(ncalendar_dir()?.join("config"))
 }
 
 pub fn parse_file(input: &str) -> Result<path::PathBuf, env::VarError> {
@@ -26,18 +48,139 @@ pub fn parse_file(input: &str) -> Result<path::PathBuf, env::VarError> {
     }
 }
 
-pub fn parse_today(input: &str) -> Result<time::Date, time::error::Parse> {
-    if input == "today" {
-        Ok(time::OffsetDateTime::now_local().unwrap().date())
-    } else {
+// Parse a weekday name like in calendar files, e.g. Fri or Thurs.
+fn parse_weekday(input: &str) -> Option<time::Weekday> {
+    Locale::default().weekday_by_name(input)
+}
+
+// Parse a date relative to the given date, e.g. +7 or next-friday.
+fn parse_relative(input: &str, today: time::Date) -> Result<Option<time::Date>, ArgError> {
+    let overflow = || ArgError::UnknownDate(input.to_string());
+
+    let days: i64 = match input {
+        "today" => 0,
+        "tomorrow" => 1,
+        "yesterday" => -1,
+        _ => {
+            if let Some(n) = input.strip_prefix('+') {
+                n.parse::<u32>().map_err(|_| overflow())?.into()
+            } else if let Some(n) = input.strip_prefix('-') {
+                -i64::from(n.parse::<u32>().map_err(|_| overflow())?)
+            } else if let Some(w) = input.strip_prefix("next-") {
+                let wday = parse_weekday(w).ok_or_else(overflow)?;
+                let diff = wday.number_days_from_monday() as i64
+                    - today.weekday().number_days_from_monday() as i64;
+                (diff + 6).rem_euclid(7) + 1
+            } else if let Some(w) = input.strip_prefix("last-") {
+                let wday = parse_weekday(w).ok_or_else(overflow)?;
+                let diff = today.weekday().number_days_from_monday() as i64
+                    - wday.number_days_from_monday() as i64;
+                -((diff + 6).rem_euclid(7) + 1)
+            } else if parse_weekday(input).is_some() {
+                return Err(ArgError::AmbiguousDate(
+                    input.to_string(),
+                    "use next-<weekday> or last-<weekday>",
+                ));
+            } else {
+                return Ok(None);
+            }
+        }
+    };
+
+    today
+        .checked_add(time::Duration::days(days))
+        .map(Some)
+        .ok_or_else(overflow)
+}
+
+// Parse a day and month as DD.MM. (using the current year) or DD.MM.YYYY.
+fn parse_dotted<F>(input: &str, today: F) -> Result<time::Date, ArgError>
+where
+    F: FnOnce() -> Result<time::Date, ArgError>,
+{
+    let invalid = || ArgError::UnknownDate(input.to_string());
+
+    let fields: Vec<&str> = input.split('.').collect();
+    let (day, mon, year) = match fields[..] {
+        [d, m, ""] => (d, m, today()?.year()),
+        [_, _, y] if y.len() != 4 => {
+            return Err(ArgError::AmbiguousDate(input.to_string(), "use DD.MM.YYYY"))
+        }
+        [d, m, y] => (d, m, y.parse().map_err(|_| invalid())?),
+        _ => return Err(invalid()),
+    };
+
+    let day: u8 = day.parse().map_err(|_| invalid())?;
+    let mon: u8 = mon.parse().map_err(|_| invalid())?;
+    let mon = time::Month::try_from(mon)?;
+
+    Ok(time::Date::from_calendar_date(year, mon, day)?)
+}
+
+fn parse_date<F>(input: &str, today: F) -> Result<time::Date, ArgError>
+where
+    F: FnOnce() -> Result<time::Date, ArgError>,
+{
+    let is_digits = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+
+    if input.len() == 10 && is_digits(&input.replace('-', "")) {
+        let fmt = format_description!("[year]-[month]-[day]");
+        Ok(time::Date::parse(input, &fmt)?)
+    } else if input.contains('.') {
+        parse_dotted(input, today)
+    } else if input.contains('/') {
+        Err(ArgError::AmbiguousDate(
+            input.to_string(),
+            "use YYYY-MM-DD or DD.MM.YYYY",
+        ))
+    } else if is_digits(input) {
+        if input.len() != 8 {
+            return Err(ArgError::AmbiguousDate(
+                input.to_string(),
+                "use DDMMYYYY or YYYY-MM-DD",
+            ));
+        }
+
         let fmt = format_description!("[day][month][year]");
-        time::Date::parse(input, &fmt)
+        Ok(time::Date::parse(input, &fmt)?)
+    } else {
+        match parse_relative(input, today()?)? {
+            Some(date) => Ok(date),
+            None => Err(ArgError::UnknownDate(input.to_string())),
+        }
     }
 }
 
-pub fn parse_days(days: &str) -> Result<time::Duration, ParseIntError> {
-    let days = days.parse::<u32>()?;
-    Ok(time::Duration::days(days.into()))
+pub fn parse_today(input: &str) -> Result<time::Date, ArgError> {
+    parse_date(input, || Ok(time::OffsetDateTime::now_local()?.date()))
+}
+
+pub fn parse_priority(input: &str) -> Result<Priority, ArgError> {
+    input
+        .parse()
+        .map_err(|_| ArgError::InvalidPriority(input.to_string()))
+}
+
+pub fn parse_days(input: &str) -> Result<Period, ArgError> {
+    if input == "eom" {
+        return Ok(Period::MonthBoundary);
+    }
+
+    let (num, unit) = match input.find(|c: char| !c.is_ascii_digit()) {
+        Some(idx) => input.split_at(idx),
+        None => (input, "d"),
+    };
+
+    let n = num
+        .parse::<u32>()
+        .map_err(|_| ArgError::InvalidPeriod(input.to_string()))?;
+    match unit {
+        "d" => Ok(Period::Days(n)),
+        "w" => Ok(Period::Weeks(n)),
+        "m" => Ok(Period::Months(n)),
+        "y" => Ok(Period::Years(n)),
+        _ => Err(ArgError::InvalidPeriod(input.to_string())),
+    }
 }
 
 ////////////////////////////////////////////////////////////////////////
@@ -51,5 +194,67 @@ mod tests {
     fn today_parser() {
         assert_eq!(parse_today("02012022"), Ok(date!(2022 - 01 - 02)));
         assert_eq!(parse_today("12122000"), Ok(date!(2000 - 12 - 12)));
+        assert_eq!(parse_today("2024-12-20"), Ok(date!(2024 - 12 - 20)));
+        assert_eq!(parse_today("20.12.2024"), Ok(date!(2024 - 12 - 20)));
+    }
+
+    #[test]
+    fn days_parser() {
+        assert_eq!(parse_days("3"), Ok(Period::Days(3)));
+        assert_eq!(parse_days("3d"), Ok(Period::Days(3)));
+        assert_eq!(parse_days("2w"), Ok(Period::Weeks(2)));
+        assert_eq!(parse_days("1m"), Ok(Period::Months(1)));
+        assert_eq!(parse_days("1y"), Ok(Period::Years(1)));
+        assert_eq!(parse_days("eom"), Ok(Period::MonthBoundary));
+        assert!(parse_days("2x").is_err());
+        assert!(parse_days("w").is_err());
+    }
+
+    #[test]
+    fn relative_date_parser() {
+        // Wednesday
+        let today = || Ok(date!(2024 - 12 - 18));
+
+        assert_eq!(parse_date("today", today), Ok(date!(2024 - 12 - 18)));
+        assert_eq!(parse_date("tomorrow", today), Ok(date!(2024 - 12 - 19)));
+        assert_eq!(parse_date("+7", today), Ok(date!(2024 - 12 - 25)));
+        assert_eq!(parse_date("-3", today), Ok(date!(2024 - 12 - 15)));
+        assert_eq!(parse_date("20.12.", today), Ok(date!(2024 - 12 - 20)));
+
+        assert_eq!(parse_date("next-friday", today), Ok(date!(2024 - 12 - 20)));
+        assert_eq!(parse_date("next-Wed", today), Ok(date!(2024 - 12 - 25)));
+        assert_eq!(parse_date("last-monday", today), Ok(date!(2024 - 12 - 16)));
+        assert_eq!(parse_date("last-wed", today), Ok(date!(2024 - 12 - 11)));
+        assert_eq!(parse_date("next-thurs", today), Ok(date!(2024 - 12 - 19)));
+    }
+
+    #[test]
+    fn ambiguous_date_parser() {
+        let today = || Ok(date!(2024 - 12 - 18));
+
+        assert!(matches!(
+            parse_date("friday", today),
+            Err(ArgError::AmbiguousDate(_, _))
+        ));
+        assert!(matches!(
+            parse_date("201224", today),
+            Err(ArgError::AmbiguousDate(_, _))
+        ));
+        assert!(matches!(
+            parse_date("12/20/2024", today),
+            Err(ArgError::AmbiguousDate(_, _))
+        ));
+        assert!(matches!(
+            parse_date("20.12.24", today),
+            Err(ArgError::AmbiguousDate(_, _))
+        ));
+        assert!(matches!(
+            parse_date("next-fooday", today),
+            Err(ArgError::UnknownDate(_))
+        ));
+        assert!(matches!(
+            parse_date("31.02.", today),
+            Err(ArgError::OutOfRange(_))
+        ));
     }
 }